@@ -1,9 +1,79 @@
 #version 330 core
-out vec4 FragColor;
 
-uniform vec4 punk_color;
+#include "punk_common.glsl"
+
+#ifdef PUNK_TEXTURE
+in vec2 TexCoords;
+#endif
+
+#ifdef PUNK_VERTEX_COLOR
+in vec4 VertexColor;
+#endif
+
+#ifdef PUNK_SDF
+// Largeurs en fraction de l'étalement du champ de distance, de 0 à 0.5.
+uniform float punk_sdf_outline_width;
+uniform vec4 punk_sdf_outline_color;
+uniform float punk_sdf_glow_width;
+uniform vec4 punk_sdf_glow_color;
+
+// Superpose deux couleurs non prémultipliées.
+vec4 punk_over(vec4 top, vec4 bottom)
+{
+    float alpha = top.a + bottom.a * (1.0 - top.a);
+    vec3 color = top.rgb * top.a + bottom.rgb * bottom.a * (1.0 - top.a);
+
+    return vec4(color / max(alpha, 0.0001), alpha);
+}
+
+// La texture contient la distance au bord du glyphe, 0.5 sur le bord. Le lissage suit la taille
+// d'un pixel à l'écran, le bord reste donc net quelle que soit l'échelle.
+vec4 punk_sdf(vec4 color, vec2 coords)
+{
+    float field = texture(punk_texture, coords).r;
+    float smoothing = max(fwidth(field) * 0.5, 0.0001);
+
+    float outline_edge = 0.5 - punk_sdf_outline_width;
+    float glow_edge = outline_edge - max(punk_sdf_glow_width, 0.0001);
+
+    float fill = smoothstep(0.5 - smoothing, 0.5 + smoothing, field);
+    float outline = smoothstep(outline_edge - smoothing, outline_edge + smoothing, field);
+    float glow = smoothstep(glow_edge, outline_edge, field);
+
+    // L'opacité du texte s'applique aussi au contour et au halo.
+    vec4 glow_color = punk_sdf_glow_color;
+    glow_color.a *= glow * color.a;
+
+    vec4 outline_color = punk_sdf_outline_color;
+    outline_color.a *= outline * color.a;
+
+    vec4 result = punk_over(outline_color, glow_color);
+    result = punk_over(vec4(color.rgb, color.a * fill), result);
+
+    return result;
+}
+#endif
+
+out vec4 FragColor;
 
 void main()
 {
-    FragColor = punk_color;
-} 
+    vec4 color = punk_color;
+
+#ifdef PUNK_VERTEX_COLOR
+    color *= VertexColor;
+#endif
+
+#if defined(PUNK_SDF)
+    FragColor = punk_sdf(color, TexCoords);
+#elif defined(PUNK_TEXTURE)
+    FragColor = color * punk_sample(TexCoords);
+#else
+    FragColor = color;
+#endif
+
+#ifdef PUNK_ALPHA_TEST
+    if (FragColor.a < PUNK_ALPHA_TEST)
+        discard;
+#endif
+}
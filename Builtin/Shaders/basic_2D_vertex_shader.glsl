@@ -1,11 +1,29 @@
 #version 330 core
 
+#include "punk_common.glsl"
+
+#ifdef PUNK_TEXTURE
+layout (location = 0) in vec4 vertex;
+out vec2 TexCoords;
+#else
 layout (location = 0) in vec3 aPos;
+#endif
 
-uniform mat4 punk_model;
-uniform mat4 punk_projection;
+#ifdef PUNK_VERTEX_COLOR
+layout (location = 1) in vec4 aColor;
+out vec4 VertexColor;
+#endif
 
 void main()
 {
-    gl_Position = punk_projection * punk_model * vec4(aPos, 1.0f);
+#ifdef PUNK_TEXTURE
+    gl_Position = punk_transform(vertex.xy);
+    TexCoords = vertex.zw;
+#else
+    gl_Position = punk_transform(aPos.xy);
+#endif
+
+#ifdef PUNK_VERTEX_COLOR
+    VertexColor = aColor;
+#endif
 }
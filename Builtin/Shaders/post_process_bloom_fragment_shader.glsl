@@ -0,0 +1,32 @@
+#version 330 core
+in vec2 TexCoords;
+out vec4 FragColor;
+
+uniform sampler2D punk_texture;
+uniform vec2 punk_resolution;
+uniform float threshold;
+uniform float radius;
+uniform float intensity;
+
+void main()
+{
+    vec3 color = texture(punk_texture, TexCoords).rgb;
+    vec2 texel = radius / punk_resolution;
+
+    vec3 glow = vec3(0.0);
+    float total = 0.0;
+
+    // Flou gaussien approximatif sur les zones les plus lumineuses.
+    for (int x = -3; x <= 3; x++) {
+        for (int y = -3; y <= 3; y++) {
+            float weight = exp(-float(x * x + y * y) / 8.0);
+            vec3 sampled = texture(punk_texture, TexCoords + vec2(x, y) * texel).rgb;
+            float brightness = max(sampled.r, max(sampled.g, sampled.b));
+
+            glow += sampled * step(threshold, brightness) * weight;
+            total += weight;
+        }
+    }
+
+    FragColor = vec4(color + glow / total * intensity, 1.0);
+}
@@ -0,0 +1,32 @@
+#version 330 core
+in vec2 TexCoords;
+out vec4 FragColor;
+
+uniform sampler2D punk_texture;
+uniform sampler2D lut;
+uniform float lut_size;
+uniform float intensity;
+
+// La LUT est une bande de `lut_size` tranches de `lut_size` x `lut_size` pixels, une tranche
+// par niveau de bleu.
+vec3 lookup(vec3 color)
+{
+    float blue = color.b * (lut_size - 1.0);
+    float slice_low = floor(blue);
+    float slice_high = min(slice_low + 1.0, lut_size - 1.0);
+
+    vec2 texel = vec2(1.0 / (lut_size * lut_size), 1.0 / lut_size);
+    vec2 uv = vec2(color.r * (lut_size - 1.0), color.g * (lut_size - 1.0)) * texel + texel * 0.5;
+
+    vec3 low = texture(lut, uv + vec2(slice_low * lut_size * texel.x, 0.0)).rgb;
+    vec3 high = texture(lut, uv + vec2(slice_high * lut_size * texel.x, 0.0)).rgb;
+
+    return mix(low, high, blue - slice_low);
+}
+
+void main()
+{
+    vec3 color = texture(punk_texture, TexCoords).rgb;
+
+    FragColor = vec4(mix(color, lookup(clamp(color, 0.0, 1.0)), intensity), 1.0);
+}
@@ -0,0 +1,27 @@
+#version 330 core
+in vec2 TexCoords;
+out vec4 FragColor;
+
+uniform sampler2D punk_texture;
+uniform vec2 curvature;
+uniform float vignette;
+
+void main()
+{
+    // Déforme les coordonnées afin d'imiter un écran bombé.
+    vec2 uv = TexCoords * 2.0 - 1.0;
+    vec2 offset = abs(uv.yx) / curvature;
+    uv = uv + uv * offset * offset;
+    uv = uv * 0.5 + 0.5;
+
+    if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
+        FragColor = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    // Assombrit les bords de l'écran.
+    vec2 edge = uv * (1.0 - uv.yx);
+    float shade = pow(edge.x * edge.y * 15.0, vignette);
+
+    FragColor = vec4(texture(punk_texture, uv).rgb * clamp(shade, 0.0, 1.0), 1.0);
+}
@@ -0,0 +1,17 @@
+#version 330 core
+in vec2 TexCoords;
+out vec4 FragColor;
+
+uniform sampler2D punk_texture;
+uniform vec2 punk_source_size;
+uniform float intensity;
+
+void main()
+{
+    vec3 color = texture(punk_texture, TexCoords).rgb;
+
+    // Une ligne sombre par ligne du rendu logique.
+    float line = sin(TexCoords.y * punk_source_size.y * 3.14159265 * 2.0) * 0.5 + 0.5;
+
+    FragColor = vec4(color * mix(1.0, line, intensity), 1.0);
+}
@@ -0,0 +1,20 @@
+#version 330 core
+in vec2 TexCoords;
+out vec4 FragColor;
+
+uniform sampler2D punk_texture;
+uniform float mask_size;
+uniform float intensity;
+
+void main()
+{
+    vec3 color = texture(punk_texture, TexCoords).rgb;
+
+    // Grille d'ouverture RGB, une colonne par composante.
+    int column = int(mod(floor(gl_FragCoord.x / mask_size), 3.0));
+
+    vec3 mask = vec3(1.0 - intensity);
+    mask[column] = 1.0;
+
+    FragColor = vec4(color * mask, 1.0);
+}
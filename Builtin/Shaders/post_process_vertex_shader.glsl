@@ -2,10 +2,8 @@
 layout (location = 0) in vec4 vertex;
 out vec2 TexCoords;
 
-uniform mat4 punk_projection;
-
 void main()
 {
-    gl_Position = punk_projection * vec4(vertex.xy, 0.0, 1.0);
+    gl_Position = vec4(vertex.xy, 0.0, 1.0);
     TexCoords = vertex.zw;
-}  
+}
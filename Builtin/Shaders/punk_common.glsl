@@ -0,0 +1,22 @@
+uniform mat4 punk_projection;
+uniform mat4 punk_model;
+uniform vec4 punk_color;
+uniform sampler2D punk_texture;
+
+vec4 punk_transform(vec2 position)
+{
+#ifdef PUNK_WORLD_SPACE
+    return punk_projection * vec4(position, 0.0, 1.0);
+#else
+    return punk_projection * punk_model * vec4(position, 0.0, 1.0);
+#endif
+}
+
+vec4 punk_sample(vec2 coords)
+{
+#ifdef PUNK_ALPHA_TEXTURE
+    return vec4(1.0, 1.0, 1.0, texture(punk_texture, coords).r);
+#else
+    return texture(punk_texture, coords);
+#endif
+}
@@ -17,19 +17,16 @@ impl Default for BenchmarkManager {
 
 impl BenchmarkManager {
     pub fn bench(&mut self, function_name: String, c: impl FnOnce()) {
-        let map = match self.tests.get_mut(&function_name) {
-            Some(value) => value,
-            None => {
-                self.tests.insert(function_name.clone(), Vec::new());
-                self.tests.get_mut(&function_name).unwrap()
-            }
-        };
-
         let instant = Instant::now();
 
         (c)();
 
-        map.push(instant.elapsed());
+        self.record(function_name, instant.elapsed());
+    }
+
+    // Ajoute une mesure prise ailleurs, par exemple un temps GPU relevé quelques frames plus tard.
+    pub fn record(&mut self, function_name: String, duration: Duration) {
+        self.tests.entry(function_name).or_default().push(duration);
     }
 
     pub fn print_results(&self) {
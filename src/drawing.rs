@@ -1,2 +1,4 @@
+pub mod mesh;
 pub mod rectangle;
+pub mod sprite;
 pub mod text;
@@ -0,0 +1,130 @@
+use crate::{
+    maths::{
+        mat::Mat4,
+        vec::{Vec3, Vec4},
+    },
+    renderer::{
+        blend::BlendMode,
+        data_object::DataObject,
+        draw::Draw,
+        ebo::Indices,
+        material::Material,
+        vbo::VBOType,
+        vertex_layout::{Topology, VertexLayout},
+        Renderer,
+    },
+};
+
+// Objet dont les sommets, leur format et le type de primitive sont choisis par l'utilisateur.
+#[derive(Clone)]
+pub struct Mesh {
+    data_object: DataObject,
+}
+
+impl Mesh {
+    pub fn build(
+        material: Material,
+        vertices: &[u8],
+        layout: &VertexLayout,
+        indices: Option<&Indices>,
+        topology: Topology,
+    ) -> Result<Self, String> {
+        let data_object = DataObject::build_with_layout(
+            vertices,
+            layout,
+            indices,
+            topology,
+            VBOType::StaticDraw,
+            material,
+        )?;
+
+        data_object.set_label("punk_mesh");
+
+        Ok(Self { data_object })
+    }
+
+    // Remplace tous les sommets, encodés selon le format donné à la construction.
+    pub fn set_vertices(&mut self, vertices: &[u8]) -> Result<(), String> {
+        self.data_object.set_vertices(vertices)
+    }
+
+    pub fn update_vertices(&mut self, first_vertex: usize, vertices: &[u8]) -> Result<(), String> {
+        self.data_object.update_vertices(first_vertex, vertices)
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.data_object.set_topology(topology);
+    }
+
+    pub fn set_instance_buffer(
+        &mut self,
+        data: &[u8],
+        layout: &VertexLayout,
+        instance_count: usize,
+    ) -> Result<(), String> {
+        self.data_object
+            .set_instance_buffer(data, layout, instance_count)
+    }
+
+    pub fn set_instance_count(&mut self, instance_count: usize) {
+        self.data_object.set_instance_count(instance_count);
+    }
+}
+
+impl Draw for Mesh {
+    fn draw(&self, renderer: &Renderer, projection: &Mat4<f32>) -> Result<(), String> {
+        self.data_object.draw(renderer, projection)
+    }
+
+    fn get_color(&self) -> Vec4<f32> {
+        self.data_object.get_color()
+    }
+
+    fn set_color(&mut self, color: Vec4<f32>) {
+        self.data_object.set_color(color);
+    }
+
+    fn get_position(&self) -> Vec3<f32> {
+        self.data_object.get_position()
+    }
+
+    fn get_scale(&self) -> Vec3<f32> {
+        self.data_object.get_scale()
+    }
+
+    fn set_position(&mut self, position: Vec3<f32>) {
+        self.data_object.set_position(position);
+    }
+
+    fn set_scale(&mut self, scale: Vec3<f32>) {
+        self.data_object.set_scale(scale);
+    }
+
+    fn is_visible(&self) -> bool {
+        self.data_object.is_visible()
+    }
+
+    fn set_visible(&mut self, value: bool) {
+        self.data_object.set_visible(value);
+    }
+
+    fn get_blend_mode(&self) -> BlendMode {
+        self.data_object.get_blend_mode()
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.data_object.set_blend_mode(blend_mode);
+    }
+
+    fn borrow_material(&self) -> &Material {
+        self.data_object.borrow_material()
+    }
+
+    fn borrow_material_mut(&mut self) -> &mut Material {
+        self.data_object.borrow_material_mut()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_object.set_material(material);
+    }
+}
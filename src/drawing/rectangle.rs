@@ -4,9 +4,10 @@ use crate::{
         vec::{Vec3, Vec4},
     },
     renderer::{
+        blend::BlendMode,
         data_object::{AttribPointer, DataObject},
         draw::Draw,
-        uniform::Uniform,
+        material::Material,
         vbo::VBOType,
         Renderer,
     },
@@ -48,36 +49,15 @@ impl Rectangle {
             pixel,
             vertices_size as isize,
             &vec![attrib_pointer],
-            color.clone(),
+            color,
             position,
             size,
             VBOType::StaticDraw,
         )?;
 
-        let uniforms = rect.borrow_mut_uniforms();
+        rect.set_material(Material::build(shader_program)?);
 
-        shader_program.use_it()?;
-
-        let color_name = "punk_color";
-        let mut color_uniform = Uniform::new();
-        let mut model_uniform = Uniform::new();
-        let mut projection_uniform = Uniform::new();
-
-        color_uniform.search(&shader_program, color_name)?;
-
-        let punk_model = "punk_model";
-        model_uniform.search(&shader_program, punk_model)?;
-
-        let punk_projection = "punk_projection";
-        projection_uniform.search(&shader_program, punk_projection)?;
-
-        color_uniform.send_vec4(&color)?;
-
-        uniforms.insert(color_name.to_string(), color_uniform);
-        uniforms.insert(punk_model.to_string(), model_uniform);
-        uniforms.insert(punk_projection.to_string(), projection_uniform);
-
-        rect.set_shader_program(shader_program);
+        rect.set_label("punk_rectangle");
 
         Ok(Self { data_object: rect })
     }
@@ -119,4 +99,24 @@ impl Draw for Rectangle {
     fn set_visible(&mut self, value: bool) {
         self.data_object.set_visible(value);
     }
+
+    fn get_blend_mode(&self) -> BlendMode {
+        self.data_object.get_blend_mode()
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.data_object.set_blend_mode(blend_mode);
+    }
+
+    fn borrow_material(&self) -> &Material {
+        self.data_object.borrow_material()
+    }
+
+    fn borrow_material_mut(&mut self) -> &mut Material {
+        self.data_object.borrow_material_mut()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_object.set_material(material);
+    }
 }
@@ -0,0 +1,158 @@
+use crate::{
+    maths::{
+        mat::Mat4,
+        vec::{Vec3, Vec4},
+    },
+    renderer::{
+        blend::BlendMode,
+        data_object::{AttribPointer, DataObject},
+        draw::Draw,
+        material::Material,
+        texture::Texture,
+        uniform::UniformValue,
+        vbo::VBOType,
+        Renderer,
+    },
+    shader::program::ShaderProgram,
+};
+
+use std::mem::{self, size_of};
+
+// Rectangle texturé, utilisé notamment pour afficher le contenu d'une `RenderTarget`. La
+// texture est partagée : elle reste valide même si la cible qui l'a créée est supprimée.
+pub struct Sprite {
+    data_object: DataObject,
+    texture: Texture,
+}
+
+impl Clone for Sprite {
+    fn clone(&self) -> Self {
+        Self {
+            data_object: self.data_object.clone(),
+            texture: self.texture.share(),
+        }
+    }
+}
+
+impl Sprite {
+    pub fn build(
+        shader_program: ShaderProgram,
+        texture: Texture,
+        color: Vec4<f32>,
+        position: Vec3<f32>,
+        size: Vec3<f32>,
+    ) -> Result<Self, String> {
+        let x = 0.5_f32;
+        let y = 0.5_f32;
+
+        // Les coordonnées de texture suivent la convention d'OpenGL (origine en bas à gauche),
+        // comme les textures des cibles de rendu.
+        #[rustfmt::skip]
+        let vertices = vec![
+            -x, -y, 0.0_f32, 1.0_f32,
+            x, -y, 1.0_f32, 1.0_f32,
+            -x, y, 0.0_f32, 0.0_f32,
+            x, -y, 1.0_f32, 1.0_f32,
+            -x, y, 0.0_f32, 0.0_f32,
+            x, y, 1.0_f32, 0.0_f32,
+        ];
+
+        let attrib_pointer = AttribPointer {
+            index: 0,
+            size: 4,
+            stride: (4 * mem::size_of::<f32>()) as i32,
+            offset: 0,
+        };
+
+        let vertices_size = vertices.len() * size_of::<f32>();
+
+        let mut data_object = DataObject::build(
+            vertices,
+            vertices_size as isize,
+            &vec![attrib_pointer],
+            color,
+            position,
+            size,
+            VBOType::StaticDraw,
+        )?;
+
+        data_object.set_material(Material::build(shader_program)?);
+
+        data_object.set_label("punk_sprite");
+
+        Ok(Self {
+            data_object,
+            texture,
+        })
+    }
+
+    pub fn borrow_texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = texture;
+    }
+}
+
+impl Draw for Sprite {
+    fn draw(&self, renderer: &Renderer, projection: &Mat4<f32>) -> Result<(), String> {
+        self.data_object.draw_with(
+            renderer,
+            projection,
+            &[("punk_texture", UniformValue::Texture(self.texture.get_id()))],
+        )
+    }
+
+    fn get_color(&self) -> Vec4<f32> {
+        self.data_object.get_color()
+    }
+
+    fn set_color(&mut self, color: Vec4<f32>) {
+        self.data_object.set_color(color);
+    }
+
+    fn get_position(&self) -> Vec3<f32> {
+        self.data_object.get_position()
+    }
+
+    fn get_scale(&self) -> Vec3<f32> {
+        self.data_object.get_scale()
+    }
+
+    fn set_position(&mut self, position: Vec3<f32>) {
+        self.data_object.set_position(position);
+    }
+
+    fn set_scale(&mut self, scale: Vec3<f32>) {
+        self.data_object.set_scale(scale);
+    }
+
+    fn is_visible(&self) -> bool {
+        self.data_object.is_visible()
+    }
+
+    fn set_visible(&mut self, value: bool) {
+        self.data_object.set_visible(value);
+    }
+
+    fn get_blend_mode(&self) -> BlendMode {
+        self.data_object.get_blend_mode()
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.data_object.set_blend_mode(blend_mode);
+    }
+
+    fn borrow_material(&self) -> &Material {
+        self.data_object.borrow_material()
+    }
+
+    fn borrow_material_mut(&mut self) -> &mut Material {
+        self.data_object.borrow_material_mut()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_object.set_material(material);
+    }
+}
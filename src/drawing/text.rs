@@ -1,21 +1,105 @@
-use std::{mem, os};
+use std::{cell::RefCell, f32::consts::PI, mem, ops::Range, rc::Rc};
+
+use gl::types::GLuint;
 
 use crate::{
-    gl_exec,
-    maths::vec::{Vec3, Vec4},
+    maths::{
+        mat::Mat4,
+        vec::{Vec2, Vec3, Vec4},
+    },
     renderer::{
+        blend::BlendMode,
         data_object::{AttribPointer, DataObject},
         draw::Draw,
-        uniform::Uniform,
-        vbo::VBOType,
+        font::{Charactere, DEFAULT_FONT, DEFAULT_FONT_SIZE},
+        material::Material,
+        text_layout::{TextAlign, TextBounds, TextLayout, TextLayoutOptions},
+        uniform::UniformValue,
+        vbo::{f32_bytes, VBOType},
+        Renderer,
     },
     shader::program::ShaderProgram,
 };
 
+// Nombre de copies décalées qui dessinent le contour autour de chaque glyphe.
+const OUTLINE_SAMPLES: usize = 8;
+
+// Position, coordonnées de texture et couleur.
+const TEXT_VERTEX_SIZE: usize = 8;
+
+// Couleur appliquée aux caractères `range` (en caractères, `\n` compris). Elle multiplie la
+// couleur du texte, les plages qui se chevauchent se combinent.
+#[derive(Clone)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub color: Vec4<f32>,
+}
+
+// Copie du texte dessinée derrière lui, `offset` est en pixels de la police (y vers le bas).
+#[derive(Clone)]
+pub struct TextShadow {
+    pub offset: Vec2<f32>,
+    pub color: Vec4<f32>,
+}
+
+// Contour autour des glyphes, `thickness` est en pixels de la police.
+#[derive(Clone)]
+pub struct TextOutline {
+    pub thickness: f32,
+    pub color: Vec4<f32>,
+}
+
+// Sommets d'une page de l'atlas, dessinés en un seul appel.
+#[derive(Clone)]
+struct TextBatch {
+    texture: GLuint,
+    first_vertex: usize,
+    count: usize,
+    // L'ombre et le contour gardent leur couleur, seule l'opacité du texte leur est appliquée.
+    tinted: bool,
+    // Voir `Charactere::distance_field`.
+    distance_field: bool,
+}
+
+// Copies du texte dessinées aux décalages donnés, avec la couleur de l'ombre ou du contour ou,
+// sans décoration, celle des plages.
+struct TextPass {
+    offsets: Vec<Vec2<f32>>,
+    decoration: Option<Vec4<f32>>,
+}
+
+// La mise en page ne dépend que du contenu, de la police et des options, pas du style.
+#[derive(Clone)]
+struct CachedLayout {
+    // Voir `FontContext::get_generation`.
+    generation: u64,
+    layout: Rc<TextLayout>,
+}
+
+// Sommets de toute la chaîne, placés dans le repère du texte : la position et l'échelle sont
+// appliquées par la matrice du modèle.
+#[derive(Clone)]
+struct TextMesh {
+    // Voir `FontContext::get_generation`.
+    generation: u64,
+    // Une copie du texte partage le VBO et peut en avoir remplacé le contenu.
+    vbo_revision: u64,
+    batches: Vec<TextBatch>,
+}
+
 #[derive(Clone)]
 pub struct Text {
     data_object: DataObject,
     text: String,
+    // Chaîne de polices, la première qui contient un caractère le fournit.
+    fonts: Vec<String>,
+    font_size: u32,
+    layout_options: TextLayoutOptions,
+    spans: Vec<TextSpan>,
+    shadow: Option<TextShadow>,
+    outline: Option<TextOutline>,
+    layout: RefCell<Option<CachedLayout>>,
+    mesh: RefCell<Option<TextMesh>>,
 }
 
 impl Text {
@@ -25,19 +109,29 @@ impl Text {
         position: Vec3<f32>,
         text: &str,
     ) -> Result<Self, String> {
-        let vertices_size = mem::size_of::<f32>() * 6 * 4;
-        let attrib_pointer = AttribPointer {
-            index: 0,
-            size: 4,
-            stride: (4 * mem::size_of::<f32>()) as i32,
-            offset: 0,
-        };
+        let stride = (TEXT_VERTEX_SIZE * mem::size_of::<f32>()) as i32;
+
+        let attrib_pointers = vec![
+            AttribPointer {
+                index: 0,
+                size: 4,
+                stride,
+                offset: 0,
+            },
+            AttribPointer {
+                index: 1,
+                size: 4,
+                stride,
+                offset: 4 * mem::size_of::<f32>(),
+            },
+        ];
 
+        // Le VBO est rempli au premier rendu.
         let mut data_object = DataObject::build(
             Vec::new(),
-            vertices_size as isize,
-            &vec![attrib_pointer],
-            color.clone(),
+            0,
+            &attrib_pointers,
+            color,
             position,
             Vec3 {
                 x: 1.0_f32,
@@ -47,147 +141,505 @@ impl Text {
             VBOType::DynamicDraw,
         )?;
 
-        let uniforms = data_object.borrow_mut_uniforms();
+        data_object.set_material(Material::build(shader_program)?);
 
-        shader_program.use_it()?;
+        data_object.set_label("punk_text");
 
-        let texture_name = "punk_texture";
-        let texture_color_name = "punk_texture_color";
-        let projection_name = "punk_projection";
+        Ok(Self {
+            data_object,
+            text: text.to_string(),
+            fonts: vec![DEFAULT_FONT.to_string()],
+            font_size: DEFAULT_FONT_SIZE,
+            layout_options: TextLayoutOptions::default(),
+            spans: Vec::new(),
+            shadow: None,
+            outline: None,
+            layout: RefCell::new(None),
+            mesh: RefCell::new(None),
+        })
+    }
 
-        let mut texture_uniform = Uniform::new();
-        let mut texture_color_uniform = Uniform::new();
-        let mut projection_uniform = Uniform::new();
+    // Ne refait la mise en page que si le contenu change.
+    pub fn set_text(&mut self, text: &str) {
+        if self.text == text {
+            return;
+        }
 
-        texture_uniform.search(&shader_program, texture_name)?;
-        texture_color_uniform.search(&shader_program, texture_color_name)?;
-        projection_uniform.search(&shader_program, projection_name)?;
+        self.text = text.to_string();
 
-        uniforms.insert(texture_name.to_string(), texture_uniform);
-        uniforms.insert(texture_color_name.to_string(), texture_color_uniform);
-        uniforms.insert(projection_name.to_string(), projection_uniform);
+        self.invalidate_layout();
+    }
 
-        data_object.set_shader_program(shader_program);
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
 
-        Ok(Self {
-            data_object,
-            text: text.to_string(),
-        })
+    // La police doit être chargée dans la fenêtre qui dessine le texte. La taille est en pixels
+    // avant l'échelle de l'objet.
+    pub fn set_font(&mut self, font: &str, font_size: u32) {
+        self.set_font_chain(&[font], font_size);
     }
-}
 
-impl Draw for Text {
-    fn draw(
-        &self,
-        renderer: &crate::renderer::Renderer,
-        projection: &crate::maths::mat::Mat4<f32>,
-    ) -> Result<(), String> {
-        // Dessiner un texte ne se fait pas de la même manière que les autres objets.
-        // Ici chaque lettre va être rendue une par une en modifiant le contenu du buffer OpenGL.
-
-        let punk_texture_color = "punk_texture_color";
-        let punk_projection = "punk_projection";
-
-        let texture_color_uniform = self
-            .data_object
-            .uniforms
-            .get(punk_texture_color)
-            .ok_or(format!("{punk_texture_color} uniform not found"))?;
+    // Les caractères absents de la première police sont pris dans les suivantes, dans l'ordre
+    // (par exemple une police latine, puis CJK, puis emoji). Une chaîne vide garde la police
+    // par défaut.
+    pub fn set_font_chain(&mut self, fonts: &[&str], font_size: u32) {
+        self.fonts = if fonts.is_empty() {
+            vec![DEFAULT_FONT.to_string()]
+        } else {
+            fonts.iter().map(|font| font.to_string()).collect()
+        };
+        self.font_size = font_size;
 
-        let projection_uniform = self
-            .data_object
-            .uniforms
-            .get(punk_projection)
-            .ok_or(format!("{punk_projection} uniform not found"))?;
+        self.invalidate_layout();
+    }
+
+    // Première police de la chaîne.
+    pub fn get_font(&self) -> &str {
+        &self.fonts[0]
+    }
+
+    pub fn borrow_fonts(&self) -> &Vec<String> {
+        &self.fonts
+    }
+
+    pub fn get_font_size(&self) -> u32 {
+        self.font_size
+    }
+
+    pub fn add_span(&mut self, range: Range<usize>, color: Vec4<f32>) {
+        self.spans.push(TextSpan { range, color });
+
+        self.invalidate_mesh();
+    }
+
+    // Ne change que l'opacité des caractères `range`.
+    pub fn add_alpha_span(&mut self, range: Range<usize>, alpha: f32) {
+        self.add_span(
+            range,
+            Vec4 {
+                x: 1.0_f32,
+                y: 1.0_f32,
+                z: 1.0_f32,
+                w: alpha,
+            },
+        );
+    }
+
+    pub fn clear_spans(&mut self) {
+        self.spans.clear();
+
+        self.invalidate_mesh();
+    }
+
+    pub fn borrow_spans(&self) -> &Vec<TextSpan> {
+        &self.spans
+    }
+
+    pub fn set_shadow(&mut self, shadow: Option<TextShadow>) {
+        self.shadow = shadow;
+
+        self.invalidate_mesh();
+    }
+
+    pub fn get_shadow(&self) -> Option<TextShadow> {
+        self.shadow.clone()
+    }
+
+    pub fn set_outline(&mut self, outline: Option<TextOutline>) {
+        self.outline = outline;
+
+        self.invalidate_mesh();
+    }
+
+    pub fn get_outline(&self) -> Option<TextOutline> {
+        self.outline.clone()
+    }
+
+    // Contour calculé par le shader `sdf_text_shader_program`, `width` est une fraction de
+    // l'étalement du champ de distance (de 0 à 0.5).
+    pub fn set_sdf_outline(&mut self, width: f32, color: Vec4<f32>) -> Result<(), String> {
+        let material = self.data_object.borrow_material_mut();
+
+        material.set_param("punk_sdf_outline_width", UniformValue::Float(width))?;
+        material.set_param("punk_sdf_outline_color", UniformValue::Vec4(color))
+    }
+
+    // Halo autour du contour, dont l'opacité décroît sur `width`.
+    pub fn set_sdf_glow(&mut self, width: f32, color: Vec4<f32>) -> Result<(), String> {
+        let material = self.data_object.borrow_material_mut();
+
+        material.set_param("punk_sdf_glow_width", UniformValue::Float(width))?;
+        material.set_param("punk_sdf_glow_color", UniformValue::Vec4(color))
+    }
+
+    // Largeur maximale d'une ligne en unités du monde, le texte est coupé entre les mots.
+    pub fn set_max_width(&mut self, max_width: Option<f32>) {
+        self.layout_options.max_width = max_width;
+
+        self.invalidate_layout();
+    }
+
+    pub fn set_align(&mut self, align: TextAlign) {
+        self.layout_options.align = align;
+
+        self.invalidate_layout();
+    }
 
-        self.data_object.shader_program.use_it()?;
+    // Multiplie la hauteur de ligne de la police.
+    pub fn set_line_spacing(&mut self, line_spacing: f32) {
+        self.layout_options.line_spacing = line_spacing;
 
-        let mut position = self.data_object.position.clone();
+        self.invalidate_layout();
+    }
+
+    pub fn borrow_layout_options(&self) -> &TextLayoutOptions {
+        &self.layout_options
+    }
+
+    // Boîte occupée par le texte en unités du monde, relative à sa position (la ligne de base de
+    // la première ligne). L'ombre et le contour ne sont pas comptés.
+    pub fn measure(&self, renderer: &Renderer) -> Result<TextBounds, String> {
+        let scale = self.get_draw_scale(renderer);
+
+        Ok(self.get_layout(renderer)?.bounds.scaled(scale.x, scale.y))
+    }
+
+    // Un texte dont la première police est bitmap n'est dessiné qu'à une position entière et
+    // agrandi d'un nombre entier de fois, afin que ses pixels restent nets.
+    fn is_pixel_exact(&self, renderer: &Renderer) -> bool {
+        self.fonts
+            .first()
+            .is_some_and(|font| renderer.font_context.is_bitmap_font(font))
+    }
+
+    fn get_draw_scale(&self, renderer: &Renderer) -> Vec3<f32> {
+        let scale = self.data_object.get_scale();
+
+        if !self.is_pixel_exact(renderer) {
+            return scale;
+        }
 
-        let display_size = renderer.get_display_size();
+        Vec3 {
+            x: scale.x.round().max(1.0_f32),
+            y: scale.y.round().max(1.0_f32),
+            ..scale
+        }
+    }
 
-        // Fait en sorte que l'origine soit en haut à gauche du rendu.
-        position.x -= display_size.x as f32 * 0.5_f32;
-        position.y -= display_size.y as f32 * 0.5_f32;
+    // Remplace la matrice du `DataObject` pour un texte au pixel près.
+    fn get_pixel_exact_model(&self, renderer: &Renderer) -> Mat4<f32> {
+        let position = self.data_object.get_position();
 
-        let color = self.data_object.color.clone();
-        let color = Vec3 {
-            x: color.x,
-            y: color.y,
-            z: color.z,
+        let position = Vec3 {
+            x: position.x.round(),
+            y: position.y.round(),
+            ..position
         };
 
-        texture_color_uniform.send_vec3(&color)?;
-        projection_uniform.send_mat4(&projection)?;
-
-        gl_exec!(|| gl::ActiveTexture(gl::TEXTURE0))?;
-
-        self.data_object.vao.bind()?;
-
-        // Itère à travers toutes les lettres du texte.
-        for c in self.text.chars() {
-            let charactere = renderer
-                .font_context
-                .characteres
-                .get(&c)
-                .ok_or(format!("cannot get font for {c} charactere"))?;
-
-            let xpos = position.x + charactere.bearing.x as f32 * self.data_object.scale.x;
-            let ypos = position.y
-                + (charactere.size.y - charactere.bearing.y as u32) as f32
-                    * self.data_object.scale.y;
-
-            let w = charactere.size.x as f32 * self.data_object.scale.x;
-            let h = charactere.size.y as f32 * self.data_object.scale.y;
-
-            let vertices = [
-                xpos,
-                ypos - h,
-                0.0_f32,
-                0.0_f32,
-                xpos,
-                ypos,
-                0.0_f32,
-                1.0_f32,
-                xpos + w,
-                ypos,
-                1.0_f32,
-                1.0_f32,
-                xpos,
-                ypos - h,
-                0.0_f32,
-                0.0_f32,
-                xpos + w,
-                ypos,
-                1.0_f32,
-                1.0_f32,
-                xpos + w,
-                ypos - h,
-                1.0_f32,
-                0.0_f32,
-            ];
+        let model = Mat4::translate(&Mat4::default(), &position);
+
+        Mat4::scale(&model, &self.get_draw_scale(renderer))
+    }
+
+    fn invalidate_layout(&self) {
+        self.layout.replace(None);
+        self.mesh.replace(None);
+    }
+
+    // Le style a changé, les glyphes restent à la même place.
+    fn invalidate_mesh(&self) {
+        self.mesh.replace(None);
+    }
+
+    // La mise en page est refaite si les glyphes de l'atlas ont été remplacés.
+    fn get_layout(&self, renderer: &Renderer) -> Result<Rc<TextLayout>, String> {
+        let font = &renderer.font_context;
+
+        if let Some(cached) = self.layout.borrow().as_ref() {
+            if cached.generation == font.get_generation() {
+                return Ok(Rc::clone(&cached.layout));
+            }
+        }
+
+        // L'atlas peut être vidé pendant la mise en page, les glyphes déjà lus sont alors
+        // invalides et tout est recommencé une fois.
+        for _ in 0..2 {
+            let generation = font.get_generation();
+
+            let layout = Rc::new(self.build_layout(renderer)?);
+
+            if font.get_generation() != generation {
+                continue;
+            }
+
+            *self.layout.borrow_mut() = Some(CachedLayout {
+                generation,
+                layout: Rc::clone(&layout),
+            });
+
+            return Ok(layout);
+        }
+
+        Err("glyph atlas is too small for this text".to_string())
+    }
+
+    // La largeur maximale est donnée en unités du monde, la mise en page se fait en pixels de
+    // la police.
+    fn build_layout(&self, renderer: &Renderer) -> Result<TextLayout, String> {
+        let scale = self.get_draw_scale(renderer);
+
+        let options = TextLayoutOptions {
+            max_width: self
+                .layout_options
+                .max_width
+                .map(|max_width| max_width / scale.x),
+            ..self.layout_options.clone()
+        };
 
-            // Fait le rendu du caractère.
-            gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, charactere.texture_id))?;
+        TextLayout::build(
+            &renderer.font_context,
+            &self.fonts,
+            self.font_size,
+            &self.text,
+            &options,
+            renderer.borrow_state(),
+        )
+    }
 
-            // Met à jour le contenu du buffer OpenGL.
-            self.data_object.vbo.bind()?;
-            gl_exec!(|| gl::BufferSubData(
-                gl::ARRAY_BUFFER,
-                0,
-                (mem::size_of::<f32>() * 24) as isize,
-                vertices.as_ptr() as *const os::raw::c_void
-            ))?;
+    // Reconstruit les sommets si la mise en page ou le style ont changé, ou si le VBO a été
+    // réécrit.
+    fn update_mesh(&self, renderer: &Renderer) -> Result<(), String> {
+        let vbo = self.data_object.borrow_vbo();
 
-            // Fait le rendu.
-            gl_exec!(|| gl::DrawArrays(gl::TRIANGLES, 0, 6))?;
+        let layout = self.get_layout(renderer)?;
+        let generation = renderer.font_context.get_generation();
 
-            // Avance le curseur.
-            position.x += (charactere.advance_x >> 6) as f32;
+        if let Some(mesh) = self.mesh.borrow().as_ref() {
+            if mesh.generation == generation && mesh.vbo_revision == vbo.get_revision() {
+                return Ok(());
+            }
         }
 
-        gl_exec!(|| gl::BindVertexArray(0))?;
-        gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, 0))?;
+        let (vertices, batches) = self.build_vertices(&layout);
+
+        vbo.stream(f32_bytes(&vertices))?;
+
+        *self.mesh.borrow_mut() = Some(TextMesh {
+            generation,
+            vbo_revision: vbo.get_revision(),
+            batches,
+        });
+
+        Ok(())
+    }
+
+    // Couleur propre au caractère `index`, blanche sans plage.
+    fn get_span_color(&self, index: usize) -> Vec4<f32> {
+        let mut color = Vec4 {
+            x: 1.0_f32,
+            y: 1.0_f32,
+            z: 1.0_f32,
+            w: 1.0_f32,
+        };
+
+        for span in self.spans.iter().filter(|span| span.range.contains(&index)) {
+            color *= span.color.clone();
+        }
+
+        color
+    }
+
+    // L'ombre, puis le contour et enfin le texte, un quad par caractère visible et par copie,
+    // regroupés par page de l'atlas.
+    fn build_vertices(&self, layout: &TextLayout) -> (Vec<f32>, Vec<TextBatch>) {
+        let mut passes = Vec::new();
+
+        if let Some(shadow) = self.shadow.as_ref() {
+            passes.push(TextPass {
+                offsets: vec![shadow.offset.clone()],
+                decoration: Some(shadow.color.clone()),
+            });
+        }
+
+        if let Some(outline) = self.outline.as_ref() {
+            let offsets = (0..OUTLINE_SAMPLES)
+                .map(|sample| {
+                    let angle = sample as f32 * 2.0_f32 * PI / OUTLINE_SAMPLES as f32;
+
+                    Vec2 {
+                        x: angle.cos() * outline.thickness,
+                        y: angle.sin() * outline.thickness,
+                    }
+                })
+                .collect();
+
+            passes.push(TextPass {
+                offsets,
+                decoration: Some(outline.color.clone()),
+            });
+        }
+
+        passes.push(TextPass {
+            offsets: vec![Vec2::default()],
+            decoration: None,
+        });
+
+        let mut vertices = Vec::new();
+        let mut batches = Vec::new();
+
+        for pass in passes.iter() {
+            let mut pages: Vec<(GLuint, bool, Vec<f32>)> = Vec::new();
+
+            for positioned in layout.glyphs.iter() {
+                let charactere = &positioned.glyph;
+
+                if charactere.is_empty() {
+                    continue;
+                }
+
+                let span_color = self.get_span_color(positioned.index);
+
+                // Un caractère transparent n'a ni ombre ni contour.
+                let color = match &pass.decoration {
+                    Some(color) => Vec4 {
+                        w: color.w * span_color.w,
+                        ..color.clone()
+                    },
+                    None => span_color,
+                };
+
+                let texture = charactere.texture.get_id();
+                let distance_field = charactere.distance_field;
+
+                let page = match pages
+                    .iter()
+                    .position(|(id, field, _)| *id == texture && *field == distance_field)
+                {
+                    Some(page) => page,
+                    None => {
+                        pages.push((texture, distance_field, Vec::new()));
+
+                        pages.len() - 1
+                    }
+                };
+
+                for offset in pass.offsets.iter() {
+                    push_quad(
+                        &mut pages[page].2,
+                        charactere,
+                        positioned.position.x + offset.x,
+                        positioned.position.y + offset.y,
+                        &color,
+                    );
+                }
+            }
+
+            for (texture, distance_field, page_vertices) in pages {
+                batches.push(TextBatch {
+                    texture,
+                    first_vertex: vertices.len() / TEXT_VERTEX_SIZE,
+                    count: page_vertices.len() / TEXT_VERTEX_SIZE,
+                    tinted: pass.decoration.is_none(),
+                    distance_field,
+                });
+
+                vertices.extend(page_vertices);
+            }
+        }
+
+        (vertices, batches)
+    }
+}
+
+// Deux triangles pour le glyphe placé au point `x`, `y` de la ligne de base.
+fn push_quad(vertices: &mut Vec<f32>, charactere: &Charactere, x: f32, y: f32, color: &Vec4<f32>) {
+    let scale = charactere.scale;
+
+    let xpos = x + charactere.bearing.x as f32 * scale;
+    let ypos = y + (charactere.size.y as i32 - charactere.bearing.y) as f32 * scale;
+
+    let w = charactere.size.x as f32 * scale;
+    let h = charactere.size.y as f32 * scale;
+
+    let (u0, v0) = (charactere.uv_min.x, charactere.uv_min.y);
+    let (u1, v1) = (charactere.uv_max.x, charactere.uv_max.y);
+
+    let corners = [
+        (xpos, ypos - h, u0, v0),
+        (xpos, ypos, u0, v1),
+        (xpos + w, ypos, u1, v1),
+        (xpos, ypos - h, u0, v0),
+        (xpos + w, ypos, u1, v1),
+        (xpos + w, ypos - h, u1, v0),
+    ];
+
+    for (x, y, u, v) in corners {
+        vertices.extend_from_slice(&[x, y, u, v, color.x, color.y, color.z, color.w]);
+    }
+}
+
+impl Draw for Text {
+    // Un appel de rendu par page de l'atlas utilisée, le plus souvent une seule.
+    fn draw(&self, renderer: &Renderer, projection: &Mat4<f32>) -> Result<(), String> {
+        self.update_mesh(renderer)?;
+
+        let mesh = self.mesh.borrow();
+
+        let tint = self.data_object.get_color();
+        let opacity = Vec4 {
+            x: 1.0_f32,
+            y: 1.0_f32,
+            z: 1.0_f32,
+            w: tint.w,
+        };
+
+        // Le programme doit lire les glyphes comme ils sont rangés dans l'atlas.
+        let sdf_program = self
+            .data_object
+            .borrow_material()
+            .has_uniform("punk_sdf_outline_width");
+
+        let model = self
+            .is_pixel_exact(renderer)
+            .then(|| self.get_pixel_exact_model(renderer));
+
+        for batch in mesh.iter().flat_map(|mesh| mesh.batches.iter()) {
+            match (batch.distance_field, sdf_program) {
+                (true, false) => {
+                    return Err("sdf glyphs must be drawn with sdf_text_shader_program".to_string())
+                }
+                (false, true) => {
+                    return Err(
+                        "bitmap glyphs cannot be drawn with sdf_text_shader_program".to_string()
+                    )
+                }
+                _ => (),
+            }
+
+            let color = if batch.tinted { &tint } else { &opacity };
+
+            let mut builtins = vec![
+                ("punk_texture", UniformValue::Texture(batch.texture)),
+                (
+                    "punk_texture_color",
+                    UniformValue::Vec4(self.data_object.color.clone()),
+                ),
+                ("punk_color", UniformValue::Vec4(color.clone())),
+            ];
+
+            if let Some(model) = model.as_ref() {
+                builtins.push(("punk_model", UniformValue::Mat4(model.clone())));
+            }
+
+            self.data_object.draw_range(
+                renderer,
+                projection,
+                &builtins,
+                batch.first_vertex,
+                batch.count,
+            )?;
+        }
 
         Ok(())
     }
@@ -205,6 +657,11 @@ impl Draw for Text {
     }
 
     fn set_scale(&mut self, scale: Vec3<f32>) {
+        // La largeur maximale en pixels de la police dépend de l'échelle.
+        if self.layout_options.max_width.is_some() && scale.x != self.data_object.get_scale().x {
+            self.invalidate_layout();
+        }
+
         self.data_object.set_scale(scale);
     }
 
@@ -223,4 +680,30 @@ impl Draw for Text {
     fn set_position(&mut self, position: Vec3<f32>) {
         self.data_object.set_position(position);
     }
+
+    fn get_blend_mode(&self) -> BlendMode {
+        self.data_object.get_blend_mode()
+    }
+
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.data_object.set_blend_mode(blend_mode);
+    }
+
+    fn borrow_material(&self) -> &Material {
+        self.data_object.borrow_material()
+    }
+
+    fn borrow_material_mut(&mut self) -> &mut Material {
+        self.data_object.borrow_material_mut()
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.data_object.set_material(material);
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        Text::set_text(self, text);
+
+        Ok(())
+    }
 }
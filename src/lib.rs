@@ -2,15 +2,33 @@ use std::{
     any::Any,
     cell::{Ref, RefCell},
     collections::HashMap,
+    path::PathBuf,
     rc::Rc,
 };
 
 use benchmark::BenchmarkManager;
 use maths::vec::Vec2;
 use message::MessageCaller;
-use renderer::draw::Draw;
+use renderer::{
+    bitmap_font::BitmapFont,
+    camera::Camera2D,
+    capture::Image,
+    draw::Draw,
+    font::GlyphMode,
+    material::Material,
+    post_process::PostProcessPass,
+    recorder::Recorder,
+    stats::RenderStats,
+    text_layout::{TextBounds, TextLayoutOptions},
+    texture::Texture,
+    uniform::UniformValue,
+};
 use resource::Resource;
-use shader::program::ShaderProgram;
+use sdl2::keyboard::Keycode;
+use shader::{
+    program::ShaderProgram,
+    registry::{ShaderRegistry, ShaderSource},
+};
 use types::UserData;
 use window::{user_input::Keys, window::Window};
 
@@ -29,7 +47,7 @@ pub struct GraphPunk<'a> {
     windows: HashMap<String, Window<'a>>,
     resources: Resource,
     drawing_objects: Vec<Box<dyn Draw>>,
-    shaders_program: HashMap<String, ShaderProgram>,
+    shaders_program: ShaderRegistry,
     benchmark: BenchmarkManager,
 }
 
@@ -39,7 +57,7 @@ impl<'a> GraphPunk<'a> {
             windows: HashMap::new(),
             resources: Resource::new(),
             drawing_objects: Vec::new(),
-            shaders_program: HashMap::new(),
+            shaders_program: ShaderRegistry::new(),
             benchmark: BenchmarkManager::default(),
         }
     }
@@ -127,6 +145,510 @@ impl<'a> GraphPunk<'a> {
             .set_grid_pixel(&mut self.drawing_objects, x, y, value)
     }
 
+    pub fn window_create_render_target(
+        &mut self,
+        unique_id: &str,
+        target_id: &str,
+        size: Vec2<i32>,
+        depth_stencil: bool,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .create_render_target(target_id, size, depth_stencil)
+    }
+
+    // Les objets aux index donnés sont dessinés dans la cible de rendu au lieu de la fenêtre.
+    pub fn window_redirect_to_render_target(
+        &mut self,
+        unique_id: &str,
+        target_id: &str,
+        indexes: Vec<usize>,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        let render_target = window
+            .borrow_renderer_mut()
+            .borrow_render_target_mut(target_id)
+            .ok_or("no render target found".to_string())?;
+
+        render_target.set_drawing_indexes(indexes);
+
+        Ok(())
+    }
+
+    // La texture est partagée avec la cible, par exemple pour l'afficher avec un `Sprite`.
+    pub fn window_get_render_target_texture(
+        &self,
+        unique_id: &str,
+        target_id: &str,
+    ) -> Result<Texture, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        let render_target = window
+            .borrow_renderer()
+            .borrow_render_target(target_id)
+            .ok_or("no render target found".to_string())?;
+
+        Ok(render_target.borrow_texture().share())
+    }
+
+    pub fn window_add_post_process_pass(
+        &mut self,
+        unique_id: &str,
+        pass: PostProcessPass,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window.borrow_renderer_mut().post_process.add_pass(pass);
+
+        Ok(())
+    }
+
+    // Charge une passe dont le fragment shader est lu depuis un fichier, elle profite ainsi du
+    // rechargement à chaud.
+    pub fn window_add_post_process_file(
+        &mut self,
+        unique_id: &str,
+        name: &str,
+        fragment_path: &str,
+    ) -> Result<(), String> {
+        let program = self.shaders_program.load(
+            name,
+            PostProcessPass::vertex_source(),
+            ShaderSource::file(fragment_path),
+        )?;
+
+        self.window_add_post_process_pass(unique_id, PostProcessPass::from_program(name, program)?)
+    }
+
+    pub fn window_remove_post_process_pass(
+        &mut self,
+        unique_id: &str,
+        pass_name: &str,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .post_process
+            .remove_pass(pass_name)
+            .ok_or("no post process pass found".to_string())?;
+
+        Ok(())
+    }
+
+    pub fn window_set_post_process_pass_enabled(
+        &mut self,
+        unique_id: &str,
+        pass_name: &str,
+        value: bool,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        let pass = window
+            .borrow_renderer_mut()
+            .post_process
+            .borrow_pass_mut(pass_name)
+            .ok_or("no post process pass found".to_string())?;
+
+        pass.set_enabled(value);
+
+        Ok(())
+    }
+
+    pub fn window_set_post_process_param(
+        &mut self,
+        unique_id: &str,
+        pass_name: &str,
+        param_name: &str,
+        value: UniformValue,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        let pass = window
+            .borrow_renderer_mut()
+            .post_process
+            .borrow_pass_mut(pass_name)
+            .ok_or("no post process pass found".to_string())?;
+
+        pass.set_param(param_name, value)
+    }
+
+    // La capture est enregistrée à la fin de la prochaine frame affichée.
+    pub fn window_request_screenshot(&mut self, unique_id: &str, path: &str) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .request_screenshot(PathBuf::from(path));
+
+        Ok(())
+    }
+
+    pub fn window_set_screenshot_hotkey(
+        &mut self,
+        unique_id: &str,
+        hotkey: Option<Keycode>,
+        directory: &str,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window.set_screenshot_hotkey(hotkey, PathBuf::from(directory));
+
+        Ok(())
+    }
+
+    pub fn window_capture_render_target(
+        &self,
+        unique_id: &str,
+        target_id: &str,
+    ) -> Result<Image, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window.borrow_renderer().capture_render_target(target_id)
+    }
+
+    pub fn window_capture_grid(&self, unique_id: &str) -> Result<Image, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer()
+            .capture_grid(&self.drawing_objects, window.borrow_background_color())
+    }
+
+    pub fn window_save_grid_png(&self, unique_id: &str, path: &str) -> Result<(), String> {
+        self.window_capture_grid(unique_id)?.save_png(path)
+    }
+
+    // L'enregistrement démarre immédiatement.
+    pub fn window_start_recording(
+        &mut self,
+        unique_id: &str,
+        mut recorder: Recorder,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        recorder.start()?;
+
+        window.borrow_renderer_mut().set_recorder(Some(recorder))
+    }
+
+    // Met en pause ou reprend l'enregistrement, les fichiers ne sont finalisés qu'à l'arrêt.
+    pub fn window_toggle_recording(&mut self, unique_id: &str) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .borrow_recorder_mut()
+            .ok_or("no recorder found".to_string())?
+            .toggle()
+    }
+
+    pub fn window_stop_recording(&mut self, unique_id: &str) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window.borrow_renderer_mut().set_recorder(None)
+    }
+
+    pub fn window_set_camera(&mut self, unique_id: &str, camera: Camera2D) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window.borrow_renderer_mut().set_camera(camera);
+
+        Ok(())
+    }
+
+    pub fn window_get_camera(&self, unique_id: &str) -> Result<Camera2D, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        Ok(window.borrow_renderer().borrow_camera().clone())
+    }
+
+    // Appels OpenGL évités par le cache d'état pendant la dernière frame rendue.
+    pub fn window_get_skipped_gl_calls(&self, unique_id: &str) -> Result<u64, String> {
+        Ok(self
+            .window_get_render_stats(unique_id)?
+            .skipped_state_changes)
+    }
+
+    pub fn window_get_render_stats(&self, unique_id: &str) -> Result<RenderStats, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        Ok(window.borrow_renderer().get_frame_stats())
+    }
+
+    // Mesure le temps GPU des passes de rendu, activé par défaut si le contexte le permet.
+    pub fn window_set_gpu_timing(&mut self, unique_id: &str, value: bool) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .borrow_gpu_timer_mut()
+            .set_enabled(value);
+
+        Ok(())
+    }
+
+    pub fn window_set_render_target_camera(
+        &mut self,
+        unique_id: &str,
+        target_id: &str,
+        camera: Camera2D,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        let render_target = window
+            .borrow_renderer_mut()
+            .borrow_render_target_mut(target_id)
+            .ok_or("no render target found".to_string())?;
+
+        render_target.set_camera(camera);
+
+        Ok(())
+    }
+
+    // Convertit une position en pixels de la fenêtre, par exemple celle de la souris.
+    pub fn window_screen_to_world(
+        &self,
+        unique_id: &str,
+        screen: Vec2<f32>,
+    ) -> Result<Vec2<f32>, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        Ok(window.borrow_renderer().screen_to_world(&screen))
+    }
+
+    pub fn window_world_to_screen(
+        &self,
+        unique_id: &str,
+        world: Vec2<f32>,
+    ) -> Result<Vec2<f32>, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        Ok(window.borrow_renderer().world_to_screen(&world))
+    }
+
+    // Les polices appartiennent au rendu de chaque fenêtre, un `Text` les désigne par leur nom.
+    pub fn window_load_font_file(
+        &mut self,
+        unique_id: &str,
+        name: &str,
+        path: &str,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .font_context
+            .load_font_file(name, path)
+    }
+
+    pub fn window_load_font_bytes(
+        &mut self,
+        unique_id: &str,
+        name: &str,
+        data: Vec<u8>,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .font_context
+            .load_font_bytes(name, data)
+    }
+
+    pub fn window_load_bdf_file(
+        &mut self,
+        unique_id: &str,
+        name: &str,
+        path: &str,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .font_context
+            .load_bdf_file(name, path)
+    }
+
+    pub fn window_add_bitmap_font(
+        &mut self,
+        unique_id: &str,
+        name: &str,
+        font: BitmapFont,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .font_context
+            .add_bitmap_font(name, font);
+
+        Ok(())
+    }
+
+    pub fn window_set_glyph_mode(
+        &mut self,
+        unique_id: &str,
+        glyph_mode: GlyphMode,
+    ) -> Result<(), String> {
+        let window = self
+            .windows
+            .get_mut(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer_mut()
+            .font_context
+            .set_glyph_mode(glyph_mode);
+
+        Ok(())
+    }
+
+    pub fn window_get_font_names(&self, unique_id: &str) -> Result<Vec<String>, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        Ok(window.borrow_renderer().font_context.get_font_names())
+    }
+
+    pub fn window_measure_text(
+        &self,
+        unique_id: &str,
+        font: &str,
+        size: u32,
+        text: &str,
+        options: &TextLayoutOptions,
+    ) -> Result<TextBounds, String> {
+        let window = self
+            .windows
+            .get(unique_id)
+            .ok_or("no window found".to_string())?;
+
+        window
+            .borrow_renderer()
+            .measure_text(font, size, text, options)
+    }
+
+    // Retourne l'index de l'objet, utilisable avec les cibles de rendu.
+    pub fn add_drawing_object(&mut self, drawing_object: Box<dyn Draw>) -> usize {
+        self.drawing_objects.push(drawing_object);
+
+        self.drawing_objects.len() - 1
+    }
+
+    pub fn set_drawing_object_material(
+        &mut self,
+        index: usize,
+        material: Material,
+    ) -> Result<(), String> {
+        self.drawing_objects
+            .get_mut(index)
+            .ok_or(format!("no drawing object at index {index}"))?
+            .set_material(material);
+
+        Ok(())
+    }
+
+    pub fn set_drawing_object_param(
+        &mut self,
+        index: usize,
+        name: &str,
+        value: UniformValue,
+    ) -> Result<(), String> {
+        self.drawing_objects
+            .get_mut(index)
+            .ok_or(format!("no drawing object at index {index}"))?
+            .borrow_material_mut()
+            .set_param(name, value)
+    }
+
+    pub fn set_drawing_object_text(&mut self, index: usize, text: &str) -> Result<(), String> {
+        self.drawing_objects
+            .get_mut(index)
+            .ok_or(format!("no drawing object at index {index}"))?
+            .set_text(text)
+    }
+
     pub fn add_resource(&mut self, unique_id: &str, data: impl Any) {
         self.resources.add(unique_id, data);
     }
@@ -149,7 +671,52 @@ impl<'a> GraphPunk<'a> {
 
     pub fn init_basic_resources(&mut self) -> Result<(), String> {
         self.resources
-            .init_basic_resources(&mut self.drawing_objects)
+            .init_basic_resources(&mut self.drawing_objects, &mut self.shaders_program)
+    }
+
+    // Un programme du même nom est recompilé en place, les objets qui l'utilisent en profitent
+    // directement.
+    pub fn load_shader_program(
+        &mut self,
+        name: &str,
+        vertex_path: &str,
+        fragment_path: &str,
+    ) -> Result<ShaderProgram, String> {
+        self.shaders_program
+            .load_files(name, vertex_path, fragment_path)
+    }
+
+    pub fn get_shader_program(&self, name: &str) -> Option<ShaderProgram> {
+        self.shaders_program.get(name)
+    }
+
+    // Permutation d'un programme enregistré, compilée une seule fois par jeu de `#define`.
+    pub fn get_shader_variant(
+        &mut self,
+        name: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<ShaderProgram, String> {
+        self.shaders_program.get_variant(name, defines)
+    }
+
+    // Fichier utilisable avec `#include "name"` dans les shaders chargés ensuite.
+    pub fn add_shader_include(&mut self, name: &str, source: &str) {
+        self.shaders_program
+            .borrow_preprocessor_mut()
+            .add_file(name, source);
+    }
+
+    pub fn reload_shader_program(&mut self, name: &str) -> Result<(), String> {
+        self.shaders_program.reload(name)
+    }
+
+    // Surveille les fichiers des shaders chargés et les recompile lorsqu'ils changent.
+    pub fn set_shader_hot_reload(&mut self, value: bool) {
+        self.shaders_program.set_watching(value);
+    }
+
+    pub fn borrow_shader_registry_mut(&mut self) -> &mut ShaderRegistry {
+        &mut self.shaders_program
     }
 
     pub fn run_window(
@@ -167,6 +734,7 @@ impl<'a> GraphPunk<'a> {
             &mut self.drawing_objects,
             message_caller,
             &mut self.benchmark,
+            &mut self.shaders_program,
         )
     }
 
@@ -185,6 +753,19 @@ impl<'a> GraphPunk<'a> {
     }
 }
 
+impl Drop for GraphPunk<'_> {
+    fn drop(&mut self) {
+        // Les objets OpenGL doivent être supprimés avant la destruction des contextes, qui
+        // appartiennent aux fenêtres.
+        self.drawing_objects.clear();
+        self.resources.clear();
+        self.shaders_program = ShaderRegistry::new();
+        self.windows.clear();
+
+        renderer::gl_object::report_leaks();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,6 +5,7 @@ use super::{
     one::One,
     rad::Radians,
     sin::Sinus,
+    sqrt::Sqrt,
     vec::{Vec3, Vec4},
     zero::{ConvertFrom, Zero},
 };
@@ -51,7 +52,7 @@ impl Mat4Index {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Mat4<T> {
     data: [T; 4 * 4],
 }
@@ -89,6 +90,206 @@ impl<T> ops::Index<Mat4Index> for Mat4<T> {
     }
 }
 
+impl<T> ops::IndexMut<Mat4Index> for Mat4<T> {
+    fn index_mut(&mut self, index: Mat4Index) -> &mut Self::Output {
+        &mut self.data[index.value()]
+    }
+}
+
+// Indexation par (ligne, colonne), de 0 à 3.
+impl<T> ops::Index<(usize, usize)> for Mat4<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &Self::Output {
+        assert!(row < 4 && column < 4, "Mat4 index out of bounds");
+
+        &self.data[row * 4 + column]
+    }
+}
+
+impl<T> ops::IndexMut<(usize, usize)> for Mat4<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut Self::Output {
+        assert!(row < 4 && column < 4, "Mat4 index out of bounds");
+
+        &mut self.data[row * 4 + column]
+    }
+}
+
+impl<T: Copy> From<[[T; 4]; 4]> for Mat4<T> {
+    fn from(rows: [[T; 4]; 4]) -> Self {
+        Self::from_rows(rows)
+    }
+}
+
+impl<T: Copy> Mat4<T> {
+    // Les données sont rangées ligne par ligne.
+    pub fn from_rows(rows: [[T; 4]; 4]) -> Self {
+        #[rustfmt::skip]
+        let matrix = Self {
+            data: [
+                rows[0][0], rows[0][1], rows[0][2], rows[0][3],
+                rows[1][0], rows[1][1], rows[1][2], rows[1][3],
+                rows[2][0], rows[2][1], rows[2][2], rows[2][3],
+                rows[3][0], rows[3][1], rows[3][2], rows[3][3],
+            ],
+        };
+
+        matrix
+    }
+
+    pub fn from_columns(columns: [[T; 4]; 4]) -> Self {
+        Self::from_rows(columns).transpose()
+    }
+
+    pub fn to_rows(&self) -> [[T; 4]; 4] {
+        [0, 1, 2, 3].map(|row| [0, 1, 2, 3].map(|column| self.data[row * 4 + column]))
+    }
+
+    pub fn to_columns(&self) -> [[T; 4]; 4] {
+        self.transpose().to_rows()
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut data = self.data;
+
+        for row in 0..4 {
+            for column in 0..4 {
+                data[column * 4 + row] = self.data[row * 4 + column];
+            }
+        }
+
+        Self { data }
+    }
+}
+
+impl<T> Mat4<T>
+where
+    T: ops::Add<Output = T>
+        + ops::Mul<Output = T>
+        + ops::Sub<Output = T>
+        + ops::Neg<Output = T>
+        + ops::Div<Output = T>
+        + Zero<Output = T>
+        + One<Output = T>
+        + PartialEq
+        + Copy,
+{
+    // Cofacteurs des six déterminants 2×2 des deux premières et des deux dernières lignes.
+    fn minors(&self) -> ([T; 6], [T; 6]) {
+        let m = &self.data;
+
+        let top = [
+            m[0] * m[5] - m[1] * m[4],
+            m[0] * m[6] - m[2] * m[4],
+            m[0] * m[7] - m[3] * m[4],
+            m[1] * m[6] - m[2] * m[5],
+            m[1] * m[7] - m[3] * m[5],
+            m[2] * m[7] - m[3] * m[6],
+        ];
+
+        let bottom = [
+            m[8] * m[13] - m[9] * m[12],
+            m[8] * m[14] - m[10] * m[12],
+            m[8] * m[15] - m[11] * m[12],
+            m[9] * m[14] - m[10] * m[13],
+            m[9] * m[15] - m[11] * m[13],
+            m[10] * m[15] - m[11] * m[14],
+        ];
+
+        (top, bottom)
+    }
+
+    pub fn determinant(&self) -> T {
+        let (a, b) = self.minors();
+
+        a[0] * b[5] - a[1] * b[4] + a[2] * b[3] + a[3] * b[2] - a[4] * b[1] + a[5] * b[0]
+    }
+
+    // Retourne `None` si la matrice n'est pas inversible.
+    pub fn inverse(&self) -> Option<Self> {
+        let m = &self.data;
+        let (a, b) = self.minors();
+
+        let determinant =
+            a[0] * b[5] - a[1] * b[4] + a[2] * b[3] + a[3] * b[2] - a[4] * b[1] + a[5] * b[0];
+
+        if determinant == T::zero() {
+            return None;
+        }
+
+        let inverse = T::one() / determinant;
+
+        let adjugate = [
+            m[5] * b[5] - m[6] * b[4] + m[7] * b[3],
+            -(m[1] * b[5]) + m[2] * b[4] - m[3] * b[3],
+            m[13] * a[5] - m[14] * a[4] + m[15] * a[3],
+            -(m[9] * a[5]) + m[10] * a[4] - m[11] * a[3],
+            -(m[4] * b[5]) + m[6] * b[2] - m[7] * b[1],
+            m[0] * b[5] - m[2] * b[2] + m[3] * b[1],
+            -(m[12] * a[5]) + m[14] * a[2] - m[15] * a[1],
+            m[8] * a[5] - m[10] * a[2] + m[11] * a[1],
+            m[4] * b[4] - m[5] * b[2] + m[7] * b[0],
+            -(m[0] * b[4]) + m[1] * b[2] - m[3] * b[0],
+            m[12] * a[4] - m[13] * a[2] + m[15] * a[0],
+            -(m[8] * a[4]) + m[9] * a[2] - m[11] * a[0],
+            -(m[4] * b[3]) + m[5] * b[1] - m[6] * b[0],
+            m[0] * b[3] - m[1] * b[1] + m[2] * b[0],
+            -(m[12] * a[3]) + m[13] * a[1] - m[14] * a[0],
+            m[8] * a[3] - m[9] * a[1] + m[10] * a[0],
+        ];
+
+        Some(Self {
+            data: adjugate.map(|value| value * inverse),
+        })
+    }
+}
+
+impl<T> Mat4<T>
+where
+    T: ops::Add<Output = T>
+        + ops::Mul<Output = T>
+        + ops::Sub<Output = T>
+        + ops::Neg<Output = T>
+        + ops::Div<Output = T>
+        + Zero<Output = T>
+        + One<Output = T>
+        + Sqrt<Type = T>
+        + Copy,
+{
+    // Vue placée en `eye` et tournée vers `center`, `up` donne le haut de l'image. Le repère
+    // est celui d'OpenGL : la caméra regarde vers -Z.
+    pub fn look_at(eye: &Vec3<T>, center: &Vec3<T>, up: &Vec3<T>) -> Self {
+        let forward = Vec3::normalize(&Vec3 {
+            x: center.x - eye.x,
+            y: center.y - eye.y,
+            z: center.z - eye.z,
+        });
+        let side = Vec3::normalize(&Vec3::cross(&forward, up));
+        let up = Vec3::cross(&side, &forward);
+
+        Self {
+            data: [
+                side.x,
+                side.y,
+                side.z,
+                -Vec3::dot(&side, eye),
+                up.x,
+                up.y,
+                up.z,
+                -Vec3::dot(&up, eye),
+                -forward.x,
+                -forward.y,
+                -forward.z,
+                Vec3::dot(&forward, eye),
+                T::zero(),
+                T::zero(),
+                T::zero(),
+                T::one(),
+            ],
+        }
+    }
+}
+
 impl<T> ops::Mul<Vec4<T>> for Mat4<T>
 where
     T: ops::Mul<Output = T> + ops::Add<Output = T> + Copy,
@@ -354,17 +555,17 @@ where
         Self::mul(&m, &Self { data })
     }
 
-    pub fn ortho(left: i32, right: i32, bottom: i32, top: i32, near: f32, far: f32) -> Self {
+    pub fn ortho(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Self {
         Self {
             data: [
-                T::convert_from(2.0_f32 / (right - left) as f32),
+                T::convert_from(2.0_f32 / (right - left)),
                 T::zero(),
                 T::zero(),
-                T::convert_from(-((right + left) as f32 / (right - left) as f32)),
+                T::convert_from(-((right + left) / (right - left))),
                 T::zero(),
-                T::convert_from(2.0_f32 / (top - bottom) as f32),
+                T::convert_from(2.0_f32 / (top - bottom)),
                 T::zero(),
-                T::convert_from(-((top + bottom) as f32 / (top - bottom) as f32)),
+                T::convert_from(-((top + bottom) / (top - bottom))),
                 T::zero(),
                 T::zero(),
                 T::convert_from(-2.0_f32 / (far - near)),
@@ -377,7 +578,206 @@ where
         }
     }
 
+    // `fov_y` est l'angle de vue vertical en degrés, `aspect` la largeur divisée par la hauteur.
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0_f32 / (fov_y.to_radians() / 2.0_f32).tan();
+
+        Self {
+            data: [
+                T::convert_from(f / aspect),
+                T::zero(),
+                T::zero(),
+                T::zero(),
+                T::zero(),
+                T::convert_from(f),
+                T::zero(),
+                T::zero(),
+                T::zero(),
+                T::zero(),
+                T::convert_from((far + near) / (near - far)),
+                T::convert_from(2.0_f32 * far * near / (near - far)),
+                T::zero(),
+                T::zero(),
+                -T::one(),
+                T::zero(),
+            ],
+        }
+    }
+
     pub fn borrow_data(&self) -> &[T; 4 * 4] {
         &self.data
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5_f32;
+
+    fn assert_mat_eq(m1: &Mat4<f32>, m2: &Mat4<f32>) {
+        for (v1, v2) in m1.borrow_data().iter().zip(m2.borrow_data().iter()) {
+            assert!((v1 - v2).abs() < EPSILON, "{m1:?} != {m2:?}");
+        }
+    }
+
+    fn sample() -> Mat4<f32> {
+        Mat4::from_rows([
+            [2.0, 0.0, 1.0, 3.0],
+            [1.0, 1.0, 0.0, -2.0],
+            [0.0, 4.0, 1.0, 1.0],
+            [1.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    #[test]
+    fn test_mat4_rows_and_columns() {
+        let m = sample();
+
+        assert_eq!(m[(0, 3)], 3.0);
+        assert_eq!(m[Mat4Index::W1], 3.0);
+        assert_eq!(m.to_rows()[2], [0.0, 4.0, 1.0, 1.0]);
+        assert_eq!(m.to_columns()[1], [0.0, 1.0, 4.0, 0.0]);
+        assert_mat_eq(&Mat4::from_columns(m.to_columns()), &m);
+        assert_mat_eq(&Mat4::from(m.to_rows()), &m);
+    }
+
+    #[test]
+    fn test_mat4_index_mut() {
+        let mut m: Mat4<f32> = Mat4::default();
+
+        m[(1, 3)] = 5.0;
+        m[Mat4Index::X3] = -1.0;
+
+        assert_eq!(m.to_rows()[1], [0.0, 1.0, 0.0, 5.0]);
+        assert_eq!(m.to_rows()[2], [-1.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_mat4_transpose() {
+        let m = sample();
+        let t = m.transpose();
+
+        for row in 0..4 {
+            for column in 0..4 {
+                assert_eq!(m[(row, column)], t[(column, row)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mat4_determinant() {
+        assert_eq!(Mat4::<f32>::default().determinant(), 1.0);
+        assert!((sample().determinant() - 12.0).abs() < EPSILON);
+
+        let scale = Mat4::scale(
+            &Mat4::default(),
+            &Vec3 {
+                x: 2.0_f32,
+                y: 3.0_f32,
+                z: 4.0_f32,
+            },
+        );
+
+        assert!((scale.determinant() - 24.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_mat4_inverse() {
+        let m = sample();
+        let inverse = m.inverse().expect("matrix is invertible");
+
+        assert_mat_eq(&Mat4::mul(&m, &inverse), &Mat4::default());
+        assert_mat_eq(&Mat4::mul(&inverse, &m), &Mat4::default());
+
+        let translate = Mat4::translate(
+            &Mat4::default(),
+            &Vec3 {
+                x: 3.0_f32,
+                y: -2.0_f32,
+                z: 1.0_f32,
+            },
+        );
+        let expected = Mat4::translate(
+            &Mat4::default(),
+            &Vec3 {
+                x: -3.0_f32,
+                y: 2.0_f32,
+                z: -1.0_f32,
+            },
+        );
+
+        assert_mat_eq(&translate.inverse().unwrap(), &expected);
+    }
+
+    #[test]
+    fn test_mat4_singular_inverse() {
+        let m = Mat4::from_rows([
+            [1.0_f32, 2.0, 3.0, 4.0],
+            [2.0, 4.0, 6.0, 8.0],
+            [0.0, 1.0, 0.0, 1.0],
+            [1.0, 0.0, 1.0, 0.0],
+        ]);
+
+        assert_eq!(m.determinant(), 0.0);
+        assert!(m.inverse().is_none());
+    }
+
+    #[test]
+    fn test_mat4_ortho() {
+        let m: Mat4<f32> = Mat4::ortho(-1.5, 2.5, -1.0, 3.0, -1.0, 1.0);
+
+        let left_bottom = Mat4::mul(
+            &m,
+            &Mat4::from_columns([[-1.5, -1.0, 0.0, 1.0], [0.0; 4], [0.0; 4], [0.0; 4]]),
+        );
+        let right_top = Mat4::mul(
+            &m,
+            &Mat4::from_columns([[2.5, 3.0, 0.0, 1.0], [0.0; 4], [0.0; 4], [0.0; 4]]),
+        );
+
+        assert_eq!(m[(0, 0)], 0.5);
+        assert_eq!(left_bottom.to_columns()[0], [-1.0, -1.0, 0.0, 1.0]);
+        assert_eq!(right_top.to_columns()[0], [1.0, 1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_mat4_perspective() {
+        let m: Mat4<f32> = Mat4::perspective(90.0, 2.0, 1.0, 3.0);
+
+        let expected = Mat4::from_rows([
+            [0.5, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, -2.0, -3.0],
+            [0.0, 0.0, -1.0, 0.0],
+        ]);
+
+        assert_mat_eq(&m, &expected);
+    }
+
+    #[test]
+    fn test_mat4_look_at() {
+        let eye = Vec3 {
+            x: 0.0_f32,
+            y: 0.0_f32,
+            z: 5.0_f32,
+        };
+        let center = Vec3::default();
+        let up = Vec3 {
+            x: 0.0_f32,
+            y: 1.0_f32,
+            z: 0.0_f32,
+        };
+
+        let expected = Mat4::translate(
+            &Mat4::default(),
+            &Vec3 {
+                x: 0.0_f32,
+                y: 0.0_f32,
+                z: -5.0_f32,
+            },
+        );
+
+        assert_mat_eq(&Mat4::look_at(&eye, &center, &up), &expected);
+    }
+}
@@ -1,61 +1,131 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use blend::BlendMode;
+use camera::Camera2D;
+use capture::Image;
 use font::FontContext;
 use gl::types::GLint;
+use gpu_timer::GpuTimer;
+use post_process::PostProcessChain;
+use recorder::{RecordSource, Recorder};
+use render_target::RenderTarget;
 use sdl2::video::GLContext;
+use state_cache::GlStateCache;
+use stats::RenderStats;
+use text_layout::{TextBounds, TextLayout, TextLayoutOptions};
 
-use crate::maths::{mat::Mat4, vec::Vec2};
+use crate::{
+    maths::{mat::Mat4, vec::Vec2},
+    types::RGB,
+};
 
 use self::draw::Draw;
 
+pub mod bitmap_font;
+pub mod blend;
+pub mod camera;
+pub mod capture;
 pub mod data_object;
+pub mod debug;
 pub mod draw;
+pub mod ebo;
 pub mod font;
+pub mod gl_object;
+pub mod glyph_atlas;
+pub mod glyph_cache;
+pub mod gpu_timer;
+pub mod material;
+pub mod post_process;
+pub mod recorder;
+pub mod rect_packer;
+pub mod render_target;
+pub mod sdf;
+pub mod state_cache;
+pub mod stats;
+pub mod text_layout;
+pub mod texture;
 pub mod uniform;
 pub mod vao;
 pub mod vbo;
+pub mod vertex_layout;
 
 #[macro_export]
 macro_rules! gl_exec {
     ( $closure:expr ) => {{
         unsafe {
-            $crate::renderer::clear_errors();
+            if $crate::renderer::debug::error_checks_enabled() {
+                $crate::renderer::clear_errors();
+
+                $closure();
 
-            $closure();
+                $crate::renderer::check_errors(stringify!($closure))
+            } else {
+                $closure();
 
-            $crate::renderer::check_errors(stringify!($closure))
+                Ok(())
+            }
         }
     }};
 }
 
 pub struct Renderer {
-    context: GLContext,
     display_size: Vec2<i32>,
+    viewport_size: Vec2<i32>,
     pub(crate) aspect_ratio: f32,
     pub(crate) left: f32,
     pub(crate) bottom: f32,
     pub(crate) projection: Mat4<f32>,
+    pub(crate) camera: Camera2D,
     pub(crate) font_context: FontContext,
+    // Dessinées dans l'ordre de création, une cible peut ainsi afficher celles créées avant.
+    pub(crate) render_targets: Vec<(String, RenderTarget)>,
+    pub(crate) post_process: PostProcessChain,
+    pending_screenshots: Vec<PathBuf>,
+    pub(crate) recorder: Option<Recorder>,
+    pub(crate) state: GlStateCache,
+    pub(crate) gpu_timer: GpuTimer,
+    frame_stats: RenderStats,
+    // Les champs sont libérés dans l'ordre de déclaration : le contexte doit rester valide
+    // jusqu'à la suppression des objets OpenGL ci-dessus.
+    context: GLContext,
 }
 
 impl Renderer {
     pub fn build(context: GLContext, display_size: Vec2<i32>) -> Result<Self, String> {
-        gl_exec!(|| gl::Enable(gl::BLEND))?;
-        gl_exec!(|| gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA))?;
+        let state = GlStateCache::new();
+
+        state.set_blend_mode(BlendMode::Alpha)?;
 
         let font_context = FontContext::build()?;
 
+        let camera = Camera2D::centered_on_display(&display_size);
+
         Ok(Self {
             context,
             display_size,
+            viewport_size: Vec2::default(),
             aspect_ratio: 1.0_f32,
             left: 0.0_f32,
             bottom: 0.0_f32,
             projection: Mat4::default(),
+            camera,
             font_context,
+            render_targets: Vec::new(),
+            post_process: PostProcessChain::default(),
+            pending_screenshots: Vec::new(),
+            recorder: None,
+            state,
+            gpu_timer: GpuTimer::new(),
+            frame_stats: RenderStats::default(),
         })
     }
 
+    // Replace aussi la caméra de la fenêtre au centre du nouveau rendu.
     pub fn set_display_size(&mut self, display_size: Vec2<i32>) {
+        self.camera = Camera2D::centered_on_display(&display_size);
         self.display_size = display_size;
+
+        self.update_letterbox();
     }
 
     pub fn get_display_size(&self) -> Vec2<i32> {
@@ -138,19 +208,342 @@ impl Renderer {
         Ok(())
     }
 
-    pub fn set_viewport_size(&self, width: i32, height: i32) -> Result<(), String> {
+    // Ne change l'état d'OpenGL que si le mode diffère du précédent.
+    pub fn set_blend_mode(&self, blend_mode: BlendMode) -> Result<(), String> {
+        self.state.set_blend_mode(blend_mode)
+    }
+
+    // À appeler lorsque l'état de mélange a été modifié sans passer par le `Renderer`.
+    pub fn invalidate_blend_mode(&self) {
+        self.state.invalidate_blend_mode();
+    }
+
+    pub fn borrow_state(&self) -> &GlStateCache {
+        &self.state
+    }
+
+    // Des objets OpenGL ont pu être créés ou liés depuis la frame précédente, l'état mémorisé
+    // est donc oublié. Les compteurs de la frame écoulée sont mis de côté.
+    pub fn begin_frame(&mut self) {
+        self.state.invalidate();
+        self.frame_stats = stats::take_frame_stats();
+    }
+
+    // Compteurs de la dernière frame terminée.
+    pub fn get_frame_stats(&self) -> RenderStats {
+        self.frame_stats
+    }
+
+    // Boîte occupée par un texte en pixels de la police, relative à la ligne de base de sa
+    // première ligne.
+    pub fn measure_text(
+        &self,
+        font: &str,
+        size: u32,
+        text: &str,
+        options: &TextLayoutOptions,
+    ) -> Result<TextBounds, String> {
+        self.measure_text_with_fallbacks(&[font], size, text, options)
+    }
+
+    // Voir `Text::set_font_chain`.
+    pub fn measure_text_with_fallbacks(
+        &self,
+        fonts: &[&str],
+        size: u32,
+        text: &str,
+        options: &TextLayoutOptions,
+    ) -> Result<TextBounds, String> {
+        let fonts: Vec<String> = fonts.iter().map(|font| font.to_string()).collect();
+
+        let layout =
+            TextLayout::build(&self.font_context, &fonts, size, text, options, &self.state)?;
+
+        Ok(layout.bounds)
+    }
+
+    pub fn borrow_gpu_timer_mut(&mut self) -> &mut GpuTimer {
+        &mut self.gpu_timer
+    }
+
+    pub fn set_viewport_size(&mut self, width: i32, height: i32) -> Result<(), String> {
+        self.viewport_size = Vec2 {
+            x: width,
+            y: height,
+        };
+
+        self.update_letterbox();
+
         gl_exec!(|| gl::Viewport(0, 0, width as GLint, height as GLint))
     }
 
+    // Calcule la zone visible afin que le rendu logique remplisse la fenêtre sans être déformé.
+    fn update_letterbox(&mut self) {
+        if self.viewport_size.x <= 0 || self.viewport_size.y <= 0 {
+            return;
+        }
+
+        let display_aspect_ratio = self.display_size.x as f32 / self.display_size.y as f32;
+
+        // Le rapport d'aspect permet d'agrandir / réduire le rendu afin de remplir l'espace
+        // disponible.
+        self.aspect_ratio = self.viewport_size.x as f32 / self.viewport_size.y as f32;
+
+        // Si la largeur est plus grande que la hauteur alors il faut scale sur la largeur.
+        if self.aspect_ratio >= display_aspect_ratio {
+            // Viewport plus large, utilise toute la hauteur.
+            self.left =
+                -self.aspect_ratio / display_aspect_ratio * self.display_size.x as f32 / 2.0_f32;
+            self.bottom = self.display_size.y as f32 / 2.0_f32;
+        // Sinon il faut scale sur la hauteur.
+        } else {
+            // Viewport plus haut, utilise toute la largeur.
+            self.left = -self.display_size.x as f32 / 2.0_f32;
+            self.bottom =
+                display_aspect_ratio / self.aspect_ratio * self.display_size.y as f32 / 2.0_f32;
+        }
+    }
+
+    // Moitié de la zone visible dans la fenêtre, en unités du monde.
+    pub fn get_half_extents(&self) -> Vec2<f32> {
+        Vec2 {
+            x: -self.left,
+            y: self.bottom,
+        }
+    }
+
+    pub fn update_projection(&mut self) {
+        self.projection = self.camera.view_projection(&self.get_half_extents());
+    }
+
+    pub fn borrow_camera(&self) -> &Camera2D {
+        &self.camera
+    }
+
+    pub fn set_camera(&mut self, camera: Camera2D) {
+        self.camera = camera;
+    }
+
+    pub fn world_to_screen(&self, world: &Vec2<f32>) -> Vec2<f32> {
+        self.camera
+            .world_to_screen(world, &self.get_half_extents(), &self.viewport_size)
+    }
+
+    pub fn screen_to_world(&self, screen: &Vec2<f32>) -> Vec2<f32> {
+        self.camera
+            .screen_to_world(screen, &self.get_half_extents(), &self.viewport_size)
+    }
+
+    pub fn get_viewport_size(&self) -> Vec2<i32> {
+        self.viewport_size.clone()
+    }
+
+    // Revient sur le framebuffer de la fenêtre après un rendu dans une `RenderTarget`.
+    pub fn bind_default_framebuffer(&self) -> Result<(), String> {
+        gl_exec!(|| gl::BindFramebuffer(gl::FRAMEBUFFER, 0))?;
+        gl_exec!(|| gl::Viewport(
+            0,
+            0,
+            self.viewport_size.x as GLint,
+            self.viewport_size.y as GLint
+        ))
+    }
+
+    pub fn create_render_target(
+        &mut self,
+        unique_id: &str,
+        size: Vec2<i32>,
+        depth_stencil: bool,
+    ) -> Result<(), String> {
+        let render_target = RenderTarget::build(size, &self.display_size, depth_stencil)?;
+
+        render_target.set_label(unique_id);
+
+        // L'ancienne cible du même nom est libérée ici, la nouvelle garde sa place.
+        match self.borrow_render_target_mut(unique_id) {
+            Some(previous) => *previous = render_target,
+            None => self
+                .render_targets
+                .push((unique_id.to_string(), render_target)),
+        }
+
+        Ok(())
+    }
+
+    pub fn destroy_render_target(&mut self, unique_id: &str) -> Result<(), String> {
+        let index = self
+            .render_targets
+            .iter()
+            .position(|(id, _)| id == unique_id)
+            .ok_or("no render target found".to_string())?;
+
+        self.render_targets.remove(index);
+
+        Ok(())
+    }
+
+    pub fn borrow_render_target(&self, unique_id: &str) -> Option<&RenderTarget> {
+        self.render_targets
+            .iter()
+            .find(|(id, _)| id == unique_id)
+            .map(|(_, render_target)| render_target)
+    }
+
+    pub fn borrow_render_target_mut(&mut self, unique_id: &str) -> Option<&mut RenderTarget> {
+        self.render_targets
+            .iter_mut()
+            .find(|(id, _)| id == unique_id)
+            .map(|(_, render_target)| render_target)
+    }
+
+    // Dessine tous les objets redirigés vers des cibles de rendu.
+    pub fn render_targets(&self, drawing_objects: &[Box<dyn Draw>]) -> Result<(), String> {
+        for (_, render_target) in self.render_targets.iter() {
+            render_target.render(self, drawing_objects)?;
+        }
+
+        Ok(())
+    }
+
+    // Index des objets dessinés dans une cible de rendu plutôt que dans la fenêtre.
+    pub fn redirected_indexes(&self) -> HashSet<usize> {
+        self.render_targets
+            .iter()
+            .flat_map(|(_, render_target)| render_target.drawing_indexes.iter().copied())
+            .collect()
+    }
+
+    // La capture est faite à la fin de la prochaine frame, avant l'échange des buffers, car le
+    // contenu du back buffer est indéfini ensuite.
+    pub fn request_screenshot(&mut self, path: PathBuf) {
+        self.pending_screenshots.push(path);
+    }
+
+    pub fn save_pending_screenshots(&mut self) -> Result<(), String> {
+        if self.pending_screenshots.is_empty() {
+            return Ok(());
+        }
+
+        let image = self.capture_framebuffer()?;
+
+        for path in self.pending_screenshots.drain(..) {
+            image.save_png(path)?;
+        }
+
+        Ok(())
+    }
+
+    // Lit le contenu actuel de la fenêtre.
+    pub fn capture_framebuffer(&self) -> Result<Image, String> {
+        gl_exec!(|| gl::BindFramebuffer(gl::FRAMEBUFFER, 0))?;
+
+        Image::read_framebuffer(&self.viewport_size)
+    }
+
+    pub fn capture_render_target(&self, unique_id: &str) -> Result<Image, String> {
+        self.borrow_render_target(unique_id)
+            .ok_or("no render target found".to_string())?
+            .capture(self)
+    }
+
+    // Copie sans perte de la grille de pixels logique, un pixel d'image par pixel de la grille.
+    pub fn capture_grid(
+        &self,
+        drawing_objects: &[Box<dyn Draw>],
+        background_color: &RGB,
+    ) -> Result<Image, String> {
+        let mut image = Image::new(self.display_size.x as u32, self.display_size.y as u32);
+
+        for y in 0..self.display_size.y as u32 {
+            for x in 0..self.display_size.x as u32 {
+                let index = (y * self.display_size.x as u32 + x) as usize;
+
+                let pixel = drawing_objects.get(index).ok_or("no pixel found")?;
+
+                let rgba = if pixel.is_visible() {
+                    let color = pixel.get_color();
+
+                    [
+                        (color.x.clamp(0.0_f32, 1.0_f32) * 255.0_f32).round() as u8,
+                        (color.y.clamp(0.0_f32, 1.0_f32) * 255.0_f32).round() as u8,
+                        (color.z.clamp(0.0_f32, 1.0_f32) * 255.0_f32).round() as u8,
+                        (color.w.clamp(0.0_f32, 1.0_f32) * 255.0_f32).round() as u8,
+                    ]
+                } else {
+                    [
+                        background_color.r,
+                        background_color.g,
+                        background_color.b,
+                        255,
+                    ]
+                };
+
+                image.set_pixel(x, y, rgba);
+            }
+        }
+
+        Ok(image)
+    }
+
+    // Ajoute la frame courante à l'enregistrement en cours, avant l'échange des buffers.
+    pub fn record_frame(
+        &mut self,
+        drawing_objects: &[Box<dyn Draw>],
+        background_color: &RGB,
+    ) -> Result<(), String> {
+        let source = match &self.recorder {
+            Some(recorder) if recorder.is_recording() => recorder.get_source(),
+            _ => return Ok(()),
+        };
+
+        let image = match source {
+            RecordSource::Framebuffer => self.capture_framebuffer()?,
+            RecordSource::Grid => self.capture_grid(drawing_objects, background_color)?,
+        };
+
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.capture_frame(image),
+            None => Ok(()),
+        }
+    }
+
+    // Remplace l'enregistreur actuel, qui est arrêté proprement.
+    pub fn set_recorder(&mut self, recorder: Option<Recorder>) -> Result<(), String> {
+        if let Some(mut old) = self.recorder.take() {
+            old.stop()?;
+        }
+
+        self.recorder = recorder;
+
+        Ok(())
+    }
+
+    pub fn borrow_recorder_mut(&mut self) -> Option<&mut Recorder> {
+        self.recorder.as_mut()
+    }
+
+    pub fn borrow_post_process(&self) -> &PostProcessChain {
+        &self.post_process
+    }
+
+    pub fn borrow_post_process_mut(&mut self) -> &mut PostProcessChain {
+        &mut self.post_process
+    }
+
     pub fn borrow_context(&self) -> &GLContext {
         &self.context
     }
 }
 
+// Un contexte perdu peut renvoyer une erreur à chaque appel, la lecture est donc bornée.
+const MAX_ERRORS_PER_CHECK: usize = 16;
+
 pub fn clear_errors() {
     unsafe {
-        loop {
-            if gl::GetError() == gl::NO_ERROR {
+        for _ in 0..MAX_ERRORS_PER_CHECK {
+            let error = gl::GetError();
+
+            if error == gl::NO_ERROR || error == gl::CONTEXT_LOST {
                 break;
             }
         }
@@ -158,25 +551,29 @@ pub fn clear_errors() {
 }
 
 pub fn check_errors(function_name: &str) -> Result<(), String> {
-    let mut message = String::new();
-    let mut code;
+    let mut codes = Vec::new();
 
     unsafe {
-        loop {
+        for _ in 0..MAX_ERRORS_PER_CHECK {
             let error = gl::GetError();
 
             if error == gl::NO_ERROR {
                 break;
             }
 
-            code = stringify!(error);
+            codes.push(format!("{} ({error:#06x})", debug::error_name(error)));
 
-            message.push_str(&format!("[OpenGL error] {function_name}: {code}"));
+            if error == gl::CONTEXT_LOST {
+                break;
+            }
         }
     }
 
-    if message.len() > 0 {
-        return Err(message);
+    if !codes.is_empty() {
+        return Err(format!(
+            "[OpenGL error] {function_name}: {}",
+            codes.join(", ")
+        ));
     }
 
     Ok(())
@@ -0,0 +1,449 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::maths::vec::Vec2;
+
+use super::capture::Image;
+
+// Police intégrée de chiffres hexadécimaux, 4×5 pixels.
+pub const HEX_FONT: &str = "hex";
+
+// Une ligne par octet, les quatre bits de poids fort sont les pixels de gauche à droite.
+const HEX_DIGITS: [[u8; 5]; 16] = [
+    [0xF0, 0x90, 0x90, 0x90, 0xF0],
+    [0x20, 0x60, 0x20, 0x20, 0x70],
+    [0xF0, 0x10, 0xF0, 0x80, 0xF0],
+    [0xF0, 0x10, 0xF0, 0x10, 0xF0],
+    [0x90, 0x90, 0xF0, 0x10, 0x10],
+    [0xF0, 0x80, 0xF0, 0x10, 0xF0],
+    [0xF0, 0x80, 0xF0, 0x90, 0xF0],
+    [0xF0, 0x10, 0x20, 0x40, 0x40],
+    [0xF0, 0x90, 0xF0, 0x90, 0xF0],
+    [0xF0, 0x90, 0xF0, 0x10, 0xF0],
+    [0xF0, 0x90, 0xF0, 0x90, 0x90],
+    [0xE0, 0x90, 0xE0, 0x90, 0xE0],
+    [0xF0, 0x80, 0x80, 0x80, 0xF0],
+    [0xE0, 0x90, 0x90, 0x90, 0xE0],
+    [0xF0, 0x80, 0xF0, 0x80, 0xF0],
+    [0xF0, 0x80, 0xF0, 0x80, 0x80],
+];
+
+// Glyphe d'une police bitmap, une couverture de 0 ou 255 par pixel, ligne par ligne.
+#[derive(Clone)]
+pub struct BitmapGlyph {
+    pub size: Vec2<u32>,
+    // Décalage du coin haut gauche depuis le point de départ sur la ligne de base, y vers le
+    // haut comme pour FreeType.
+    pub bearing: Vec2<i32>,
+    pub advance: u32,
+    pub pixels: Vec<u8>,
+}
+
+// Police dessinée telle quelle, sans contour : chaque pixel du glyphe devient un ou plusieurs
+// pixels entiers à l'écran.
+#[derive(Clone, Default)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, BitmapGlyph>,
+    // Hauteur au-dessus et profondeur sous la ligne de base, en pixels.
+    ascent: u32,
+    descent: u32,
+    // Caractère affiché à la place de ceux qui manquent.
+    default_char: Option<char>,
+}
+
+impl BitmapFont {
+    pub fn new(ascent: u32, descent: u32) -> Self {
+        Self {
+            glyphs: HashMap::new(),
+            ascent,
+            descent,
+            default_char: None,
+        }
+    }
+
+    pub fn add_glyph(&mut self, c: char, glyph: BitmapGlyph) {
+        self.glyphs.insert(c, glyph);
+    }
+
+    pub fn set_default_char(&mut self, c: Option<char>) {
+        self.default_char = c;
+    }
+
+    // Sans compter le caractère par défaut.
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+    }
+
+    pub fn get_glyph(&self, c: char) -> Option<&BitmapGlyph> {
+        self.glyphs
+            .get(&c)
+            .or_else(|| self.default_char.and_then(|c| self.glyphs.get(&c)))
+    }
+
+    pub fn get_ascent(&self) -> u32 {
+        self.ascent
+    }
+
+    pub fn get_descent(&self) -> u32 {
+        self.descent
+    }
+
+    // Taille à laquelle la police est dessinée sans agrandissement.
+    pub fn get_pixel_size(&self) -> u32 {
+        (self.ascent + self.descent).max(1)
+    }
+
+    pub fn get_glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    // Chiffres 0 à F (majuscules et minuscules) et espace, sur une grille de 4×5 avec un pixel
+    // d'espacement.
+    pub fn hex_digits() -> Self {
+        let mut font = Self::new(5, 0);
+
+        for (digit, rows) in HEX_DIGITS.iter().enumerate() {
+            let pixels = rows
+                .iter()
+                .flat_map(|row| (0..4).map(move |x| if row & (0x80 >> x) != 0 { 255 } else { 0 }))
+                .collect();
+
+            let glyph = BitmapGlyph {
+                size: Vec2 { x: 4, y: 5 },
+                bearing: Vec2 { x: 0, y: 5 },
+                advance: 5,
+                pixels,
+            };
+
+            let c = char::from_digit(digit as u32, 16).unwrap_or('0');
+
+            font.add_glyph(c.to_ascii_uppercase(), glyph.clone());
+            font.add_glyph(c, glyph);
+        }
+
+        font.add_glyph(
+            ' ',
+            BitmapGlyph {
+                size: Vec2 { x: 0, y: 0 },
+                bearing: Vec2 { x: 0, y: 0 },
+                advance: 5,
+                pixels: Vec::new(),
+            },
+        );
+
+        font
+    }
+
+    // Image découpée en cases de `cell_width` × `cell_height`, de gauche à droite puis de haut en
+    // bas. `characters` donne le caractère de chaque case dans cet ordre. Un pixel est allumé
+    // s'il est opaque et clair.
+    pub fn from_image_grid(
+        image: &Image,
+        cell_width: u32,
+        cell_height: u32,
+        characters: &str,
+    ) -> Result<Self, String> {
+        if cell_width == 0 || cell_height == 0 {
+            return Err("font cell size must be greater than 0".to_string());
+        }
+
+        let columns = image.width / cell_width;
+        let rows = image.height / cell_height;
+
+        if characters.chars().count() > (columns * rows) as usize {
+            return Err(format!(
+                "font image has {} cells but {} characters are mapped",
+                columns * rows,
+                characters.chars().count()
+            ));
+        }
+
+        let mut font = Self::new(cell_height, 0);
+
+        for (index, c) in characters.chars().enumerate() {
+            let origin_x = (index as u32 % columns) * cell_width;
+            let origin_y = (index as u32 / columns) * cell_height;
+
+            let pixels = (0..cell_height)
+                .flat_map(|y| (0..cell_width).map(move |x| (origin_x + x, origin_y + y)))
+                .map(|(x, y)| {
+                    let [r, g, b, a] = image.get_pixel(x, y);
+
+                    if a >= 128 && r.max(g).max(b) >= 128 {
+                        255
+                    } else {
+                        0
+                    }
+                })
+                .collect();
+
+            font.add_glyph(
+                c,
+                BitmapGlyph {
+                    size: Vec2 {
+                        x: cell_width,
+                        y: cell_height,
+                    },
+                    bearing: Vec2 {
+                        x: 0,
+                        y: cell_height as i32,
+                    },
+                    advance: cell_width,
+                    pixels,
+                },
+            );
+        }
+
+        Ok(font)
+    }
+
+    pub fn load_bdf_file<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let source = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("cannot read font {}: {e}", path.as_ref().display()))?;
+
+        Self::parse_bdf(&source)
+    }
+
+    // Format Glyph Bitmap Distribution d'Adobe (BDF 2.1). Les caractères sans encodage Unicode
+    // (`ENCODING -1`) sont ignorés.
+    pub fn parse_bdf(source: &str) -> Result<Self, String> {
+        let mut font = Self::default();
+        let mut bounding_box = [0_i32; 4];
+        let mut ascent = None;
+        let mut descent = None;
+        let mut default_char = None;
+
+        let mut lines = source.lines().enumerate();
+
+        while let Some((number, line)) = lines.next() {
+            let mut words = line.split_whitespace();
+
+            match words.next() {
+                Some("FONTBOUNDINGBOX") => bounding_box = parse_numbers(words, number)?,
+                Some("FONT_ASCENT") => ascent = Some(parse_numbers::<1>(words, number)?[0]),
+                Some("FONT_DESCENT") => descent = Some(parse_numbers::<1>(words, number)?[0]),
+                Some("DEFAULT_CHAR") => {
+                    default_char = char::from_u32(parse_numbers::<1>(words, number)?[0] as u32)
+                }
+                Some("STARTCHAR") => {
+                    if let Some((c, glyph)) = parse_bdf_char(&mut lines, &bounding_box)? {
+                        font.add_glyph(c, glyph);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if font.glyphs.is_empty() {
+            return Err("BDF font contains no glyph".to_string());
+        }
+
+        // Sans propriétés, les mesures sont celles de la boîte englobante de la police.
+        font.ascent = ascent.unwrap_or(bounding_box[1] + bounding_box[3]).max(0) as u32;
+        font.descent = descent.unwrap_or(-bounding_box[3]).max(0) as u32;
+        font.default_char = default_char;
+
+        Ok(font)
+    }
+}
+
+fn parse_numbers<'a, const N: usize>(
+    mut words: impl Iterator<Item = &'a str>,
+    line: usize,
+) -> Result<[i32; N], String> {
+    let mut numbers = [0; N];
+
+    for number in numbers.iter_mut() {
+        *number = words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .ok_or(format!("malformed BDF line {}", line + 1))?;
+    }
+
+    Ok(numbers)
+}
+
+// Lit un caractère jusqu'à `ENDCHAR`.
+fn parse_bdf_char<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    bounding_box: &[i32; 4],
+) -> Result<Option<(char, BitmapGlyph)>, String> {
+    let mut encoding = -1;
+    let mut advance = bounding_box[0];
+    let mut bbx = *bounding_box;
+
+    while let Some((number, line)) = lines.next() {
+        let mut words = line.split_whitespace();
+
+        match words.next() {
+            Some("ENCODING") => encoding = parse_numbers::<1>(words, number)?[0],
+            Some("DWIDTH") => advance = parse_numbers::<1>(words, number)?[0],
+            Some("BBX") => bbx = parse_numbers(words, number)?,
+            Some("BITMAP") => {
+                let (width, height) = (bbx[0].max(0) as u32, bbx[1].max(0) as u32);
+
+                let pixel_count = width
+                    .checked_mul(height)
+                    .ok_or(format!("BDF glyph is too large on line {}", number + 1))?;
+
+                let mut pixels = Vec::with_capacity(pixel_count as usize);
+
+                // Chaque ligne est un nombre hexadécimal, complété à l'octet, bit de poids fort
+                // à gauche.
+                for _ in 0..height {
+                    let (number, row) = lines
+                        .next()
+                        .ok_or("unexpected end of BDF bitmap".to_string())?;
+
+                    let row = row.trim();
+
+                    // Découper la ligne par octets suppose des caractères ASCII.
+                    if !row.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err(format!("malformed BDF bitmap line {}", number + 1));
+                    }
+
+                    let bytes = (0..row.len() / 2)
+                        .map(|index| u8::from_str_radix(&row[index * 2..index * 2 + 2], 16))
+                        .collect::<Result<Vec<u8>, _>>()
+                        .map_err(|_| format!("malformed BDF bitmap line {}", number + 1))?;
+
+                    pixels.extend((0..width).map(|x| {
+                        let byte = bytes.get((x / 8) as usize).copied().unwrap_or(0);
+
+                        if byte & (0x80 >> (x % 8)) != 0 {
+                            255
+                        } else {
+                            0
+                        }
+                    }));
+                }
+
+                let glyph = BitmapGlyph {
+                    size: Vec2 {
+                        x: width,
+                        y: height,
+                    },
+                    bearing: Vec2 {
+                        x: bbx[2],
+                        y: bbx[3] + bbx[1],
+                    },
+                    advance: advance.max(0) as u32,
+                    pixels,
+                };
+
+                for (_, line) in lines.by_ref() {
+                    if line.trim() == "ENDCHAR" {
+                        break;
+                    }
+                }
+
+                return Ok(u32::try_from(encoding)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .map(|c| (c, glyph)));
+            }
+            Some("ENDCHAR") => return Ok(None),
+            _ => (),
+        }
+    }
+
+    Err("unexpected end of BDF file".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BDF: &str = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 6 0 -1
+STARTPROPERTIES 3
+FONT_ASCENT 5
+FONT_DESCENT 1
+DEFAULT_CHAR 63
+ENDPROPERTIES
+CHARS 3
+STARTCHAR A
+ENCODING 65
+DWIDTH 5 0
+BBX 3 2 1 -1
+BITMAP
+A0
+ 40 
+ENDCHAR
+STARTCHAR question
+ENCODING 63
+DWIDTH 4 0
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+STARTCHAR unmapped
+ENCODING -1
+DWIDTH 4 0
+BBX 1 1 0 0
+BITMAP
+80
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn test_parse_bdf() {
+        let font = BitmapFont::parse_bdf(BDF).unwrap();
+
+        assert_eq!(font.get_glyph_count(), 2);
+        assert_eq!(font.get_ascent(), 5);
+        assert_eq!(font.get_descent(), 1);
+
+        let glyph = font.get_glyph('A').unwrap();
+
+        assert_eq!((glyph.size.x, glyph.size.y), (3, 2));
+        assert_eq!((glyph.bearing.x, glyph.bearing.y), (1, 1));
+        assert_eq!(glyph.advance, 5);
+        assert_eq!(glyph.pixels, vec![255, 0, 255, 0, 255, 0]);
+    }
+
+    #[test]
+    fn test_bdf_default_char() {
+        let font = BitmapFont::parse_bdf(BDF).unwrap();
+
+        assert!(!font.has_glyph('Z'));
+        assert_eq!(font.get_glyph('Z').unwrap().advance, 4);
+    }
+
+    #[test]
+    fn test_malformed_bdf_row() {
+        let source = BDF.replace(" 40 ", "4é");
+
+        assert!(BitmapFont::parse_bdf(&source).is_err());
+    }
+
+    #[test]
+    fn test_image_grid() {
+        let mut image = Image::new(2, 1);
+
+        // Pixel de gauche blanc et opaque, celui de droite transparent.
+        image.pixels[..4].copy_from_slice(&[255, 255, 255, 255]);
+
+        let font = BitmapFont::from_image_grid(&image, 1, 1, "ab").unwrap();
+
+        assert_eq!(font.get_glyph('a').unwrap().pixels, vec![255]);
+        assert_eq!(font.get_glyph('b').unwrap().pixels, vec![0]);
+        assert!(BitmapFont::from_image_grid(&image, 1, 1, "abc").is_err());
+    }
+
+    #[test]
+    fn test_hex_digits() {
+        let font = BitmapFont::hex_digits();
+
+        let one = font.get_glyph('1').unwrap();
+
+        assert_eq!(&one.pixels[..4], &[0, 0, 255, 0]);
+        assert_eq!(&one.pixels[16..], &[0, 255, 255, 255]);
+
+        let f = font.get_glyph('F').unwrap();
+
+        assert_eq!(f.pixels, font.get_glyph('f').unwrap().pixels);
+        assert_eq!(&f.pixels[..4], &[255; 4]);
+        assert_eq!(&f.pixels[16..], &[255, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,30 @@
+use crate::gl_exec;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BlendMode {
+    #[default]
+    Alpha,
+    Additive,
+    Multiply,
+    // La couleur est déjà multipliée par son alpha.
+    Premultiplied,
+    Opaque,
+}
+
+impl BlendMode {
+    pub fn apply(&self) -> Result<(), String> {
+        let (source, destination) = match self {
+            BlendMode::Opaque => return gl_exec!(|| gl::Disable(gl::BLEND)),
+            BlendMode::Alpha => (gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA),
+            BlendMode::Additive => (gl::SRC_ALPHA, gl::ONE),
+            BlendMode::Multiply => (gl::DST_COLOR, gl::ZERO),
+            BlendMode::Premultiplied => (gl::ONE, gl::ONE_MINUS_SRC_ALPHA),
+        };
+
+        gl_exec!(|| gl::Enable(gl::BLEND))?;
+
+        // L'alpha de la destination est toujours composé de la même manière afin que les
+        // cibles de rendu restent utilisables comme textures.
+        gl_exec!(|| gl::BlendFuncSeparate(source, destination, gl::ONE, gl::ONE_MINUS_SRC_ALPHA))
+    }
+}
@@ -0,0 +1,120 @@
+use crate::maths::{
+    mat::Mat4,
+    vec::{Vec2, Vec3},
+};
+
+#[derive(Clone)]
+pub struct Camera2D {
+    // Point du monde affiché au centre de la vue.
+    pub position: Vec2<f32>,
+    pub zoom: f32,
+    // En degrés, comme `Mat4::rotate_z`.
+    pub rotation: f32,
+}
+
+impl Default for Camera2D {
+    fn default() -> Self {
+        Self {
+            position: Vec2::default(),
+            zoom: 1.0_f32,
+            rotation: 0.0_f32,
+        }
+    }
+}
+
+impl Camera2D {
+    // Caméra qui montre tout le rendu logique, l'origine étant en haut à gauche et chaque pixel
+    // de la grille centré sur ses coordonnées entières.
+    pub fn centered_on_display(display_size: &Vec2<i32>) -> Self {
+        Self {
+            position: Vec2 {
+                x: (display_size.x - 1) as f32 * 0.5_f32,
+                y: (display_size.y - 1) as f32 * 0.5_f32,
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn translate(&mut self, offset: &Vec2<f32>) {
+        self.position.x += offset.x;
+        self.position.y += offset.y;
+    }
+
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+    }
+
+    // `half_extents` est la moitié de la zone visible, en unités du monde, avec un zoom de 1.
+    // L'axe Y est orienté vers le bas.
+    pub fn view_projection(&self, half_extents: &Vec2<f32>) -> Mat4<f32> {
+        let projection = Mat4::scale(
+            &Mat4::default(),
+            &Vec3 {
+                x: 1.0_f32 / half_extents.x,
+                y: -1.0_f32 / half_extents.y,
+                z: -1.0_f32 / 100.0_f32,
+            },
+        );
+
+        let view = Mat4::scale(
+            &Mat4::default(),
+            &Vec3 {
+                x: self.zoom,
+                y: self.zoom,
+                z: 1.0_f32,
+            },
+        );
+        let view = Mat4::rotate_z(&view, &-self.rotation);
+        let view = Mat4::translate(
+            &view,
+            &Vec3 {
+                x: -self.position.x,
+                y: -self.position.y,
+                z: 0.0_f32,
+            },
+        );
+
+        Mat4::mul(&projection, &view)
+    }
+
+    // Convertit des coordonnées du monde en pixels de la vue (origine en haut à gauche).
+    pub fn world_to_screen(
+        &self,
+        world: &Vec2<f32>,
+        half_extents: &Vec2<f32>,
+        viewport_size: &Vec2<i32>,
+    ) -> Vec2<f32> {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        let dx = world.x - self.position.x;
+        let dy = world.y - self.position.y;
+
+        let view_x = (dx * cos + dy * sin) * self.zoom;
+        let view_y = (-dx * sin + dy * cos) * self.zoom;
+
+        Vec2 {
+            x: (view_x / half_extents.x + 1.0_f32) * 0.5_f32 * viewport_size.x as f32,
+            y: (view_y / half_extents.y + 1.0_f32) * 0.5_f32 * viewport_size.y as f32,
+        }
+    }
+
+    pub fn screen_to_world(
+        &self,
+        screen: &Vec2<f32>,
+        half_extents: &Vec2<f32>,
+        viewport_size: &Vec2<i32>,
+    ) -> Vec2<f32> {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+
+        let view_x = (screen.x / viewport_size.x as f32 * 2.0_f32 - 1.0_f32) * half_extents.x;
+        let view_y = (screen.y / viewport_size.y as f32 * 2.0_f32 - 1.0_f32) * half_extents.y;
+
+        let dx = (view_x * cos - view_y * sin) / self.zoom;
+        let dy = (view_x * sin + view_y * cos) / self.zoom;
+
+        Vec2 {
+            x: self.position.x + dx,
+            y: self.position.y + dy,
+        }
+    }
+}
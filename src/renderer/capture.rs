@@ -0,0 +1,126 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    os,
+    path::Path,
+};
+
+use gl::types::GLsizei;
+
+use crate::{gl_exec, maths::vec::Vec2};
+
+// Image RGBA 8 bits, les lignes sont stockées de haut en bas.
+#[derive(Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    // Lit le contenu du framebuffer actuellement actif.
+    pub fn read_framebuffer(size: &Vec2<i32>) -> Result<Self, String> {
+        let mut image = Self::new(size.x as u32, size.y as u32);
+
+        gl_exec!(|| gl::PixelStorei(gl::PACK_ALIGNMENT, 1))?;
+        gl_exec!(|| gl::ReadPixels(
+            0,
+            0,
+            size.x as GLsizei,
+            size.y as GLsizei,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            image.pixels.as_mut_ptr() as *mut os::raw::c_void
+        ))?;
+
+        // OpenGL retourne les lignes de bas en haut.
+        image.flip_vertical();
+
+        Ok(image)
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let index = ((y * self.width + x) * 4) as usize;
+
+        self.pixels[index..index + 4].copy_from_slice(&rgba);
+    }
+
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * self.width + x) * 4) as usize;
+
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+            self.pixels[index + 3],
+        ]
+    }
+
+    pub fn flip_vertical(&mut self) {
+        let row_size = (self.width * 4) as usize;
+        let height = self.height as usize;
+
+        for y in 0..height / 2 {
+            let (top, bottom) = self.pixels.split_at_mut((height - y - 1) * row_size);
+
+            top[y * row_size..(y + 1) * row_size].swap_with_slice(&mut bottom[..row_size]);
+        }
+    }
+
+    // Les images en niveaux de gris, RGB ou à palette sont converties en RGBA.
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+
+        let info = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+
+        let mut image = Self::new(info.width, info.height);
+
+        let channels = info.color_type.samples();
+
+        for (pixel, source) in image
+            .pixels
+            .chunks_exact_mut(4)
+            .zip(buffer[..info.buffer_size()].chunks_exact(channels))
+        {
+            let rgba = match source {
+                [l] => [*l, *l, *l, 255],
+                [l, a] => [*l, *l, *l, *a],
+                [r, g, b] => [*r, *g, *b, 255],
+                [r, g, b, a] => [*r, *g, *b, *a],
+                _ => [0, 0, 0, 0],
+            };
+
+            pixel.copy_from_slice(&rgba);
+        }
+
+        Ok(image)
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|e| e.to_string())
+    }
+}
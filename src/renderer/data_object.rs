@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{cell::Cell, mem, ptr, rc::Rc};
 
 use gl::types::GLsizei;
 
@@ -8,27 +8,61 @@ use crate::{
         mat::Mat4,
         vec::{Vec3, Vec4},
     },
-    shader::program::ShaderProgram,
 };
 
 use super::{
-    uniform::Uniform,
+    blend::BlendMode,
+    ebo::{Indices, EBO},
+    material::Material,
+    stats,
+    uniform::UniformValue,
     vao::VAO,
     vbo::{VBOType, VBO},
+    vertex_layout::{AttribType, Topology, VertexAttrib, VertexLayout},
     Renderer,
 };
 
-#[derive(Clone)]
 pub struct DataObject {
-    pub(crate) vertices_number: usize,
+    // Partagé entre les copies comme le VBO, dont il suit le contenu.
+    pub(crate) vertices_number: Rc<Cell<usize>>,
+    // Taille d'un sommet en octets, sert à tenir `vertices_number` à jour.
+    vertex_stride: usize,
     pub(crate) vao: VAO,
     pub(crate) vbo: VBO,
-    pub(crate) shader_program: ShaderProgram,
-    pub(crate) uniforms: HashMap<String, Uniform>,
+    pub(crate) material: Material,
     pub(crate) color: Vec4<f32>,
     pub(crate) position: Vec3<f32>,
     pub(crate) scale: Vec3<f32>,
+    pub(crate) blend_mode: BlendMode,
     visible: bool,
+    topology: Topology,
+    ebo: Option<EBO>,
+    // Données par instance (attributs avec un diviseur), dessinées `instance_count` fois.
+    instance_vbo: Option<VBO>,
+    instance_count: usize,
+}
+
+// Une copie partage le VAO, le VBO et le programme de l'original, qui ne sont supprimés qu'avec
+// le dernier objet qui les utilise.
+impl Clone for DataObject {
+    fn clone(&self) -> Self {
+        Self {
+            vertices_number: Rc::clone(&self.vertices_number),
+            vertex_stride: self.vertex_stride,
+            vao: self.vao.share(),
+            vbo: self.vbo.share(),
+            material: self.material.clone(),
+            color: self.color.clone(),
+            position: self.position.clone(),
+            scale: self.scale.clone(),
+            blend_mode: self.blend_mode,
+            visible: self.visible,
+            topology: self.topology,
+            ebo: self.ebo.as_ref().map(|ebo| ebo.share()),
+            instance_vbo: self.instance_vbo.as_ref().map(|vbo| vbo.share()),
+            instance_count: self.instance_count,
+        }
+    }
 }
 
 pub struct AttribPointer {
@@ -40,6 +74,21 @@ pub struct AttribPointer {
 
 pub type AttribPointers = Vec<AttribPointer>;
 
+impl AttribPointer {
+    // Équivalent typé, toujours en flottants non normalisés.
+    pub fn to_vertex_attrib(&self) -> VertexAttrib {
+        VertexAttrib {
+            index: self.index,
+            components: self.size,
+            attrib_type: AttribType::F32,
+            normalized: false,
+            integer: false,
+            divisor: 0,
+            offset: self.offset,
+        }
+    }
+}
+
 impl DataObject {
     pub fn build(
         vertices: Vec<f32>,
@@ -54,78 +103,319 @@ impl DataObject {
 
         vao.bind()?;
 
-        let vbo = VBO::build(vertices, size, type_)?;
+        // Sans stride explicite, les attributs sont supposés contigus.
+        let vertex_stride = match attrib_pointers.first() {
+            Some(attrib) if attrib.stride > 0 => attrib.stride as usize,
+            _ => attrib_pointers
+                .iter()
+                .map(|attrib| attrib.size as usize * mem::size_of::<f32>())
+                .sum(),
+        };
 
-        let vertices_number = vbo.borrow_vertices().len();
+        let vertices_number = mem::size_of_val(vertices.as_slice())
+            .checked_div(vertex_stride)
+            .unwrap_or(0);
 
-        let mut index = 0;
-        for attrib in attrib_pointers.iter() {
-            vao.attrib_pointer(attrib.index, attrib.size, attrib.stride, attrib.offset)?;
-            vao.enable_attrib(index)?;
+        let vbo = VBO::build(vertices, size, type_)?;
 
-            index += 1;
+        for attrib in attrib_pointers.iter() {
+            vao.set_attrib(&attrib.to_vertex_attrib(), attrib.stride as usize)?;
         }
 
         Ok(Self {
-            vertices_number,
+            vertices_number: Rc::new(Cell::new(vertices_number)),
+            vertex_stride,
             vao,
             vbo,
-            shader_program: ShaderProgram::none(),
-            uniforms: HashMap::new(),
+            material: Material::none(),
             color,
             position,
             scale,
+            blend_mode: BlendMode::default(),
             visible: true,
+            topology: Topology::default(),
+            ebo: None,
+            instance_vbo: None,
+            instance_count: 1,
         })
     }
 
-    pub fn draw(&self, renderer: &Renderer, projection: &Mat4<f32>) -> Result<(), String> {
-        let punk_model = "punk_model";
-        let punk_projection = "punk_projection";
-        let punk_color = "punk_color";
+    // `vertices` contient les sommets encodés selon `layout`. Avec des indices, les primitives
+    // sont assemblées à partir de ceux-ci plutôt que dans l'ordre des sommets.
+    pub fn build_with_layout(
+        vertices: &[u8],
+        layout: &VertexLayout,
+        indices: Option<&Indices>,
+        topology: Topology,
+        type_: VBOType,
+        material: Material,
+    ) -> Result<Self, String> {
+        if layout.get_stride() == 0 {
+            return Err("vertex layout is empty".to_string());
+        }
 
-        let mut model = Mat4::default();
+        if !vertices.len().is_multiple_of(layout.get_stride()) {
+            return Err(format!(
+                "vertex data size ({}) is not a multiple of the layout stride ({})",
+                vertices.len(),
+                layout.get_stride()
+            ));
+        }
+
+        let vao = VAO::build()?;
+
+        vao.bind()?;
+
+        let vbo = VBO::build_bytes(vertices, type_)?;
 
-        let model_uniform = self
-            .uniforms
-            .get(punk_model)
-            .ok_or(format!("{punk_model} uniform not found"))?;
+        vao.set_layout(layout)?;
 
-        let projection_uniform = self
-            .uniforms
-            .get(punk_projection)
-            .ok_or(format!("{punk_projection} uniform not found"))?;
+        // L'EBO est mémorisé par le VAO actif.
+        let ebo = match indices {
+            Some(indices) => Some(EBO::build(indices)?),
+            None => None,
+        };
 
-        let color_uniform = self
-            .uniforms
-            .get(punk_color)
-            .ok_or(format!("{punk_color} uniform not found"))?;
+        VAO::unbind()?;
 
-        self.shader_program.use_it()?;
+        Ok(Self {
+            vertices_number: Rc::new(Cell::new(vertices.len() / layout.get_stride())),
+            vertex_stride: layout.get_stride(),
+            vao,
+            vbo,
+            material,
+            color: Vec4 {
+                x: 1.0_f32,
+                y: 1.0_f32,
+                z: 1.0_f32,
+                w: 1.0_f32,
+            },
+            position: Vec3::default(),
+            scale: Vec3 {
+                x: 1.0_f32,
+                y: 1.0_f32,
+                z: 1.0_f32,
+            },
+            blend_mode: BlendMode::default(),
+            visible: true,
+            topology,
+            ebo,
+            instance_vbo: None,
+            instance_count: 1,
+        })
+    }
 
-        let mut position = self.position.clone();
+    // Ajoute un second VBO dont les attributs avancent par instance (voir
+    // `VertexLayout::divisor`).
+    pub fn set_instance_buffer(
+        &mut self,
+        data: &[u8],
+        layout: &VertexLayout,
+        instance_count: usize,
+    ) -> Result<(), String> {
+        self.vao.bind()?;
+
+        let vbo = VBO::build_bytes(data, VBOType::DynamicDraw)?;
+
+        self.vao.set_layout(layout)?;
 
-        let display_size = renderer.get_display_size();
+        VAO::unbind()?;
 
-        // Fait en sorte que l'origine soit en haut à gauche du rendu.
-        position.x -= display_size.x as f32 * 0.5_f32;
-        position.y -= display_size.y as f32 * 0.5_f32;
+        self.instance_vbo = Some(vbo);
+        self.instance_count = instance_count;
 
+        Ok(())
+    }
+
+    pub fn set_instance_count(&mut self, instance_count: usize) {
+        self.instance_count = instance_count;
+    }
+
+    pub fn get_instance_count(&self) -> usize {
+        self.instance_count
+    }
+
+    pub fn get_topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
+
+    pub fn has_indices(&self) -> bool {
+        self.ebo.is_some()
+    }
+
+    pub fn draw(&self, renderer: &Renderer, projection: &Mat4<f32>) -> Result<(), String> {
+        self.draw_with(renderer, projection, &[])
+    }
+
+    // `builtins` complète les variables `punk_*` envoyées par défaut.
+    pub fn draw_with(
+        &self,
+        renderer: &Renderer,
+        projection: &Mat4<f32>,
+        builtins: &[(&str, UniformValue)],
+    ) -> Result<(), String> {
+        self.prepare(renderer, projection, builtins)?;
+
+        let mode = self.topology.get_gl_mode();
+        let instances = self.instance_count as GLsizei;
+
+        let count = match &self.ebo {
+            Some(ebo) => ebo.get_count(),
+            None => self.get_vertices_number(),
+        };
+
+        stats::record_draw(count * self.instance_count.max(1));
+
+        match &self.ebo {
+            Some(ebo) => {
+                let count = count as GLsizei;
+                let index_type = ebo.get_index_type();
+
+                if self.instance_vbo.is_some() || self.instance_count > 1 {
+                    gl_exec!(|| gl::DrawElementsInstanced(
+                        mode,
+                        count,
+                        index_type,
+                        ptr::null(),
+                        instances
+                    ))
+                } else {
+                    gl_exec!(|| gl::DrawElements(mode, count, index_type, ptr::null()))
+                }
+            }
+            None => {
+                let count = count as GLsizei;
+
+                if self.instance_vbo.is_some() || self.instance_count > 1 {
+                    gl_exec!(|| gl::DrawArraysInstanced(mode, 0, count, instances))
+                } else {
+                    gl_exec!(|| gl::DrawArrays(mode, 0, count))
+                }
+            }
+        }
+    }
+
+    // Dessine `count` sommets à partir de `first_vertex`, sans indices ni instances.
+    pub fn draw_range(
+        &self,
+        renderer: &Renderer,
+        projection: &Mat4<f32>,
+        builtins: &[(&str, UniformValue)],
+        first_vertex: usize,
+        count: usize,
+    ) -> Result<(), String> {
+        self.prepare(renderer, projection, builtins)?;
+
+        stats::record_draw(count);
+
+        gl_exec!(|| gl::DrawArrays(
+            self.topology.get_gl_mode(),
+            first_vertex as GLsizei,
+            count as GLsizei
+        ))
+    }
+
+    // Envoie les variables et lie le VAO.
+    fn prepare(
+        &self,
+        renderer: &Renderer,
+        projection: &Mat4<f32>,
+        builtins: &[(&str, UniformValue)],
+    ) -> Result<(), String> {
+        let mut model = Mat4::default();
+
+        // L'origine du rendu est placée par la caméra.
         // TODO: rotate
-        model = Mat4::translate(&model, &position);
+        model = Mat4::translate(&model, &self.position);
         model = Mat4::scale(&model, &self.scale);
 
-        model_uniform.send_mat4(&model)?;
-        projection_uniform.send_mat4(&projection)?;
-        color_uniform.send_vec4(&self.color)?;
+        let mut uniforms = vec![
+            ("punk_model", UniformValue::Mat4(model)),
+            ("punk_projection", UniformValue::Mat4(projection.clone())),
+            ("punk_color", UniformValue::Vec4(self.color.clone())),
+        ];
+        uniforms.extend(builtins.iter().cloned());
 
-        self.vao.bind()?;
+        let state = renderer.borrow_state();
+
+        self.material.apply(state, &uniforms)?;
+
+        state.bind_vertex_array(self.vao.get_id())
+    }
 
-        gl_exec!(|| gl::DrawArrays(gl::TRIANGLES, 0, self.get_vertices_number() as GLsizei))
+    pub fn borrow_vbo(&self) -> &VBO {
+        &self.vbo
     }
 
     pub fn get_vertices_number(&self) -> usize {
+        self.vertices_number.get()
+    }
+
+    pub fn get_vertex_stride(&self) -> usize {
+        self.vertex_stride
+    }
+
+    // Nombre de sommets que le VBO peut contenir sans être réalloué.
+    pub fn get_vertex_capacity(&self) -> usize {
+        if self.vertex_stride == 0 {
+            return 0;
+        }
+
+        self.vbo.get_size() / self.vertex_stride
+    }
+
+    // Remplace tous les sommets, le VBO est agrandi si besoin. Convient aux géométries
+    // recalculées à chaque image.
+    pub fn set_vertices(&mut self, data: &[u8]) -> Result<(), String> {
+        let count = self.vertex_count_of(data)?;
+
+        self.vbo.stream(data)?;
+        self.vertices_number.set(count);
+
+        Ok(())
+    }
+
+    // Remplace les sommets à partir de `first_vertex`, sans dépasser la capacité du VBO. Les
+    // sommets écrits au-delà du nombre actuel sont ajoutés au rendu.
+    pub fn update_vertices(&mut self, first_vertex: usize, data: &[u8]) -> Result<(), String> {
+        let count = self.vertex_count_of(data)?;
+        let start = first_vertex * self.vertex_stride;
+
+        self.vbo.update(start..start + data.len(), data)?;
         self.vertices_number
+            .set(self.get_vertices_number().max(first_vertex + count));
+
+        Ok(())
+    }
+
+    // Limite le rendu aux `count` premiers sommets déjà présents dans le VBO.
+    pub fn set_vertices_number(&mut self, count: usize) -> Result<(), String> {
+        if count > self.get_vertex_capacity() {
+            return Err(format!(
+                "cannot draw {count} vertices, the vbo only holds {}",
+                self.get_vertex_capacity()
+            ));
+        }
+
+        self.vertices_number.set(count);
+
+        Ok(())
+    }
+
+    fn vertex_count_of(&self, data: &[u8]) -> Result<usize, String> {
+        if self.vertex_stride == 0 || !data.len().is_multiple_of(self.vertex_stride) {
+            return Err(format!(
+                "vertex data size ({}) is not a multiple of the vertex stride ({})",
+                data.len(),
+                self.vertex_stride
+            ));
+        }
+
+        Ok(data.len() / self.vertex_stride)
     }
 
     pub fn get_color(&self) -> Vec4<f32> {
@@ -152,6 +442,16 @@ impl DataObject {
         self.scale = scale;
     }
 
+    // Le VAO et le VBO sont partagés avec les copies, qui portent donc le même nom.
+    pub fn set_label(&self, label: &str) {
+        self.vao.set_label(&format!("{label} vao"));
+        self.vbo.set_label(&format!("{label} vbo"));
+
+        if let Some(ebo) = &self.ebo {
+            ebo.set_label(&format!("{label} ebo"));
+        }
+    }
+
     pub fn is_visible(&self) -> bool {
         self.visible
     }
@@ -160,23 +460,27 @@ impl DataObject {
         self.visible = value;
     }
 
-    pub fn borrow_vao(&self) -> &VAO {
-        &self.vao
+    pub fn get_blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
     }
 
-    pub fn borrow_shader_program(&self) -> &ShaderProgram {
-        &self.shader_program
+    pub fn borrow_vao(&self) -> &VAO {
+        &self.vao
     }
 
-    pub fn borrow_uniforms(&self) -> &HashMap<String, Uniform> {
-        &self.uniforms
+    pub fn borrow_material(&self) -> &Material {
+        &self.material
     }
 
-    pub fn borrow_mut_uniforms(&mut self) -> &mut HashMap<String, Uniform> {
-        &mut self.uniforms
+    pub fn borrow_material_mut(&mut self) -> &mut Material {
+        &mut self.material
     }
 
-    pub fn set_shader_program(&mut self, shader_program: ShaderProgram) {
-        self.shader_program = shader_program;
+    pub fn set_material(&mut self, material: Material) {
+        self.material = material;
     }
 }
@@ -0,0 +1,202 @@
+use std::{
+    collections::VecDeque,
+    ffi::{c_void, CStr, CString},
+    sync::{
+        atomic::{AtomicBool, AtomicU8, Ordering},
+        Mutex,
+    },
+};
+
+use gl::types::{GLchar, GLenum, GLsizei, GLuint};
+
+use crate::{punk_error, punk_info, punk_warning};
+
+use super::gl_object::GlObjectKind;
+
+// Nombre maximum de messages gardés dans le journal, les plus anciens sont abandonnés.
+const DEBUG_LOG_CAPACITY: usize = 256;
+
+// Désactivable à la compilation avec la fonctionnalité `no_gl_error_checks`, ou à l'exécution
+// avec `set_error_checks`.
+static ERROR_CHECKS: AtomicBool = AtomicBool::new(true);
+
+static MIN_SEVERITY: AtomicU8 = AtomicU8::new(DebugSeverity::Low as u8);
+
+static DEBUG_LOG: Mutex<VecDeque<DebugMessage>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DebugSeverity {
+    Notification = 0,
+    Low = 1,
+    Medium = 2,
+    High = 3,
+}
+
+impl DebugSeverity {
+    fn from_gl(severity: GLenum) -> Self {
+        match severity {
+            gl::DEBUG_SEVERITY_HIGH => Self::High,
+            gl::DEBUG_SEVERITY_MEDIUM => Self::Medium,
+            gl::DEBUG_SEVERITY_LOW => Self::Low,
+            _ => Self::Notification,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DebugMessage {
+    pub source: &'static str,
+    pub kind: &'static str,
+    pub id: GLuint,
+    pub severity: DebugSeverity,
+    pub message: String,
+}
+
+pub fn error_checks_enabled() -> bool {
+    !cfg!(feature = "no_gl_error_checks") && ERROR_CHECKS.load(Ordering::Relaxed)
+}
+
+// `glGetError` force une synchronisation avec le pilote, le désactiver accélère le rendu mais
+// les erreurs ne sont alors plus remontées.
+pub fn set_error_checks(value: bool) {
+    ERROR_CHECKS.store(value, Ordering::Relaxed);
+}
+
+pub fn error_name(code: GLenum) -> &'static str {
+    match code {
+        gl::NO_ERROR => "GL_NO_ERROR",
+        gl::INVALID_ENUM => "GL_INVALID_ENUM",
+        gl::INVALID_VALUE => "GL_INVALID_VALUE",
+        gl::INVALID_OPERATION => "GL_INVALID_OPERATION",
+        gl::INVALID_FRAMEBUFFER_OPERATION => "GL_INVALID_FRAMEBUFFER_OPERATION",
+        gl::OUT_OF_MEMORY => "GL_OUT_OF_MEMORY",
+        gl::STACK_UNDERFLOW => "GL_STACK_UNDERFLOW",
+        gl::STACK_OVERFLOW => "GL_STACK_OVERFLOW",
+        gl::CONTEXT_LOST => "GL_CONTEXT_LOST",
+        _ => "GL_UNKNOWN_ERROR",
+    }
+}
+
+fn source_name(source: GLenum) -> &'static str {
+    match source {
+        gl::DEBUG_SOURCE_API => "api",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+        gl::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    }
+}
+
+fn type_name(kind: GLenum) -> &'static str {
+    match kind {
+        gl::DEBUG_TYPE_ERROR => "error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated behavior",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+        gl::DEBUG_TYPE_PORTABILITY => "portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "performance",
+        gl::DEBUG_TYPE_MARKER => "marker",
+        gl::DEBUG_TYPE_PUSH_GROUP => "push group",
+        gl::DEBUG_TYPE_POP_GROUP => "pop group",
+        _ => "other",
+    }
+}
+
+extern "system" fn debug_callback(
+    source: GLenum,
+    kind: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    _length: GLsizei,
+    message: *const GLchar,
+    _user_param: *mut c_void,
+) {
+    let severity = DebugSeverity::from_gl(severity);
+
+    if (severity as u8) < MIN_SEVERITY.load(Ordering::Relaxed) || message.is_null() {
+        return;
+    }
+
+    let message = DebugMessage {
+        source: source_name(source),
+        kind: type_name(kind),
+        id,
+        severity,
+        message: unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned(),
+    };
+
+    let text = format!(
+        "[OpenGL {}] [{}] {} ({})",
+        message.source, message.kind, message.message, message.id
+    );
+
+    match severity {
+        DebugSeverity::High => punk_error!("{text}"),
+        DebugSeverity::Medium => punk_warning!("{text}"),
+        _ => punk_info!("{text}"),
+    }
+
+    if let Ok(mut log) = DEBUG_LOG.lock() {
+        if log.len() >= DEBUG_LOG_CAPACITY {
+            log.pop_front();
+        }
+
+        log.push_back(message);
+    }
+}
+
+// Branche le journal de débogage si le contexte le permet (OpenGL 4.3 ou `GL_KHR_debug`).
+// Retourne faux si la fonctionnalité n'est pas disponible.
+pub fn enable_debug_output() -> bool {
+    if !gl::DebugMessageCallback::is_loaded() {
+        return false;
+    }
+
+    unsafe {
+        gl::Enable(gl::DEBUG_OUTPUT);
+        // Les messages arrivent dans le thread et au moment de l'appel fautif.
+        gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+        gl::DebugMessageCallback(Some(debug_callback), std::ptr::null());
+    }
+
+    true
+}
+
+// Les messages moins graves que `severity` sont ignorés.
+pub fn set_debug_severity_filter(severity: DebugSeverity) {
+    MIN_SEVERITY.store(severity as u8, Ordering::Relaxed);
+}
+
+// Vide le journal et retourne les messages reçus depuis le dernier appel.
+pub fn take_debug_messages() -> Vec<DebugMessage> {
+    match DEBUG_LOG.lock() {
+        Ok(mut log) => log.drain(..).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// Nom affiché par les outils de débogage (RenderDoc, apitrace...) et dans les messages.
+pub fn set_object_label(kind: GlObjectKind, id: GLuint, label: &str) {
+    if id == 0 || !gl::ObjectLabel::is_loaded() {
+        return;
+    }
+
+    let identifier = match kind {
+        GlObjectKind::VertexArray => gl::VERTEX_ARRAY,
+        GlObjectKind::Buffer => gl::BUFFER,
+        GlObjectKind::Shader => gl::SHADER,
+        GlObjectKind::Program => gl::PROGRAM,
+        GlObjectKind::Texture => gl::TEXTURE,
+        GlObjectKind::Framebuffer => gl::FRAMEBUFFER,
+        GlObjectKind::Renderbuffer => gl::RENDERBUFFER,
+        GlObjectKind::Query => gl::QUERY,
+    };
+
+    if let Ok(label) = CString::new(label) {
+        unsafe {
+            gl::ObjectLabel(identifier, id, -1, label.as_ptr());
+        }
+    }
+}
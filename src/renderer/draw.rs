@@ -3,7 +3,7 @@ use crate::maths::{
     vec::{Vec3, Vec4},
 };
 
-use super::Renderer;
+use super::{blend::BlendMode, material::Material, Renderer};
 
 pub trait Draw {
     fn draw(&self, renderer: &Renderer, projection: &Mat4<f32>) -> Result<(), String>;
@@ -19,4 +19,19 @@ pub trait Draw {
 
     fn is_visible(&self) -> bool;
     fn set_visible(&mut self, value: bool);
+
+    // Par défaut, l'objet est mélangé avec le mode alpha et ne peut pas en changer.
+    fn get_blend_mode(&self) -> BlendMode {
+        BlendMode::default()
+    }
+    fn set_blend_mode(&mut self, _blend_mode: BlendMode) {}
+
+    fn borrow_material(&self) -> &Material;
+    fn borrow_material_mut(&mut self) -> &mut Material;
+    fn set_material(&mut self, material: Material);
+
+    // Seuls les objets qui affichent du texte le remplacent.
+    fn set_text(&mut self, _text: &str) -> Result<(), String> {
+        Err("drawing object has no text".to_string())
+    }
 }
@@ -0,0 +1,95 @@
+use std::os;
+
+use gl::types::{GLenum, GLuint};
+
+use crate::gl_exec;
+
+use super::gl_object::{GlObject, GlObjectKind};
+
+#[derive(Clone)]
+pub enum Indices {
+    U16(Vec<u16>),
+    U32(Vec<u32>),
+}
+
+impl Indices {
+    pub fn len(&self) -> usize {
+        match self {
+            Indices::U16(indices) => indices.len(),
+            Indices::U32(indices) => indices.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Tampon d'indices, il est mémorisé par le VAO actif au moment de sa création.
+pub struct EBO {
+    handle: GlObject,
+    count: usize,
+    index_type: GLenum,
+}
+
+impl EBO {
+    pub fn build(indices: &Indices) -> Result<Self, String> {
+        let mut id = 0;
+
+        gl_exec!(|| gl::GenBuffers(1, &mut id))?;
+
+        let handle = GlObject::new(GlObjectKind::Buffer, id);
+
+        gl_exec!(|| gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, id))?;
+
+        let (size, data, index_type) = match indices {
+            Indices::U16(v) => (
+                std::mem::size_of_val(v.as_slice()),
+                v.as_ptr() as *const os::raw::c_void,
+                gl::UNSIGNED_SHORT,
+            ),
+            Indices::U32(v) => (
+                std::mem::size_of_val(v.as_slice()),
+                v.as_ptr() as *const os::raw::c_void,
+                gl::UNSIGNED_INT,
+            ),
+        };
+
+        gl_exec!(|| gl::BufferData(
+            gl::ELEMENT_ARRAY_BUFFER,
+            size as isize,
+            data,
+            gl::STATIC_DRAW
+        ))?;
+
+        Ok(Self {
+            handle,
+            count: indices.len(),
+            index_type,
+        })
+    }
+
+    pub fn share(&self) -> Self {
+        Self {
+            handle: self.handle.share(),
+            count: self.count,
+            index_type: self.index_type,
+        }
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.handle.set_label(label);
+    }
+
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    pub fn get_index_type(&self) -> GLenum {
+        self.index_type
+    }
+
+    pub fn get_id(&self) -> GLuint {
+        self.handle.get_id()
+    }
+}
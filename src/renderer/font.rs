@@ -1,35 +1,206 @@
 use freetype::{
     freetype::{
-        FT_Done_Face, FT_Done_FreeType, FT_Face, FT_Init_FreeType, FT_Int32, FT_Library,
-        FT_Load_Char, FT_New_Memory_Face, FT_Set_Pixel_Sizes, FT_ULong, FT_LOAD_RENDER,
+        FT_Done_Face, FT_Done_FreeType, FT_Face, FT_Get_Char_Index, FT_Get_Kerning,
+        FT_Init_FreeType, FT_Int32, FT_Kerning_Mode, FT_Library, FT_Load_Char, FT_New_Memory_Face,
+        FT_Set_Pixel_Sizes, FT_ULong, FT_Vector, FT_FACE_FLAG_KERNING, FT_LOAD_RENDER,
     },
     succeeded,
 };
-use gl::types::{GLint, GLsizei, GLuint};
-use std::{collections::HashMap, os, ptr};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fs,
+    path::Path,
+    ptr, slice,
+};
+
+use crate::{maths::vec::Vec2, punk_error};
+
+use super::{
+    bitmap_font::{BitmapFont, HEX_FONT},
+    gl_object::{GlObject, GlObjectKind},
+    glyph_atlas::{AtlasRegion, GlyphAtlas},
+    glyph_cache::GlyphCache,
+    sdf,
+    state_cache::GlStateCache,
+    texture::TextureFilter,
+};
+
+// Police intégrée, toujours disponible.
+pub const DEFAULT_FONT: &str = "default";
+pub const DEFAULT_FONT_SIZE: u32 = 48;
+
+// En mode SDF, la distance est mesurée jusqu'à un huitième de la taille de la police autour du
+// glyphe.
+const SDF_SPREAD_DIVISOR: u32 = 8;
+// En mode SDF, les glyphes des polices vectorielles ne sont rastérisés qu'une fois, à cette
+// taille, puis agrandis ou réduits à la mise en page.
+const SDF_BASE_SIZE: u32 = 64;
+
+// Façon dont les glyphes sont rangés dans l'atlas.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GlyphMode {
+    // Couverture de chaque pixel, nette uniquement à l'échelle 1.
+    #[default]
+    Bitmap,
+    // Distance au bord du glyphe, à dessiner avec `sdf_text_shader_program` : le texte reste
+    // net quelle que soit l'échelle. Chaque glyphe est rastérisé une seule fois pour toutes les
+    // tailles. Les polices bitmap ne sont pas concernées.
+    Sdf,
+}
+
+// Les glyphes de toutes les polices et de toutes les tailles partagent le même atlas.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct GlyphKey {
+    font: usize,
+    size: u32,
+    c: char,
+}
+
+struct FontFace {
+    face: FT_Face,
+    // FreeType lit directement ces octets, ils doivent vivre aussi longtemps que la face.
+    _data: Cow<'static, [u8]>,
+    // Taille actuellement sélectionnée dans la face.
+    current_size: Cell<u32>,
+}
+
+impl FontFace {
+    fn select_size(&self, size: u32) -> Result<(), String> {
+        if self.current_size.get() == size {
+            return Ok(());
+        }
+
+        // Défini la taille de la police d'écriture.
+        let result = unsafe { FT_Set_Pixel_Sizes(self.face, 0, size) };
+
+        if !succeeded(result) {
+            return Err(format!("cannot set font size to {size}"));
+        }
+
+        self.current_size.set(size);
+
+        Ok(())
+    }
+}
+
+impl Drop for FontFace {
+    fn drop(&mut self) {
+        unsafe {
+            FT_Done_Face(self.face);
+        }
+    }
+}
+
+enum FontSource {
+    Outline(FontFace),
+    Bitmap(BitmapFont),
+}
+
+struct Font {
+    id: usize,
+    source: FontSource,
+}
 
-use crate::{gl_exec, maths::vec::Vec2, punk_error};
+impl Font {
+    // Une police bitmap n'est agrandie que d'un nombre entier de fois, au moins une.
+    fn get_bitmap_scale(font: &BitmapFont, size: u32) -> u32 {
+        (size / font.get_pixel_size()).max(1)
+    }
+}
+
+// Mesures verticales d'une police à une taille donnée, en pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LineMetrics {
+    // Hauteur au-dessus de la ligne de base.
+    pub ascender: f32,
+    // Profondeur sous la ligne de base, négative.
+    pub descender: f32,
+    // Distance entre deux lignes de base.
+    pub line_height: f32,
+}
 
 pub struct FontContext {
+    // Les faces sont libérées avant la librairie, voir `Drop`.
+    fonts: HashMap<String, Font>,
+    next_font_id: usize,
+    // Incrémenté à chaque police chargée ou retirée.
+    fonts_revision: u64,
+    // Les glyphes sont rastérisés au premier usage, pendant le rendu.
+    characteres: RefCell<GlyphCache<GlyphKey, Charactere>>,
+    atlas: RefCell<GlyphAtlas>,
+    // Glyphes des polices bitmap, sans filtrage.
+    pixel_atlas: RefCell<GlyphAtlas>,
+    // Boîtes affichées à la place des caractères absents de la police, par taille et selon
+    // qu'elles sont un champ de distance, rangées dans l'atlas au premier besoin.
+    fallbacks: RefCell<HashMap<(u32, bool), Charactere>>,
+    // Présence de chaque caractère déjà demandé dans chaque police, par identifiant de police.
+    coverage: RefCell<HashMap<(usize, char), bool>>,
+    glyph_mode: GlyphMode,
     lib: FT_Library,
-    face_font: FT_Face,
-    pub(crate) characteres: HashMap<char, Charactere>,
 }
 
 pub struct Charactere {
-    pub(crate) texture_id: GLuint,
+    // Page de l'atlas, vide pour un glyphe sans pixels comme l'espace.
+    pub(crate) texture: GlObject,
+    pub(crate) uv_min: Vec2<f32>,
+    pub(crate) uv_max: Vec2<f32>,
     pub(crate) size: Vec2<u32>,
     pub(crate) bearing: Vec2<i32>,
     pub(crate) advance_x: i64,
+    // Rapport entre la taille demandée et celle du bitmap, qui multiplie les mesures ci-dessus.
+    pub(crate) scale: f32,
+    // Les pixels sont un champ de distance, à dessiner avec `sdf_text_shader_program`.
+    pub(crate) distance_field: bool,
+}
+
+impl Charactere {
+    // La page de l'atlas est partagée, elle n'est supprimée qu'avec la dernière copie.
+    pub fn share(&self) -> Self {
+        Self {
+            texture: self.texture.share(),
+            uv_min: self.uv_min.clone(),
+            uv_max: self.uv_max.clone(),
+            size: self.size.clone(),
+            bearing: self.bearing.clone(),
+            advance_x: self.advance_x,
+            scale: self.scale,
+            distance_field: self.distance_field,
+        }
+    }
+
+    fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+
+        self
+    }
+
+    // Avance horizontale en pixels, à la taille demandée.
+    pub fn get_advance(&self) -> f32 {
+        (self.advance_x >> 6) as f32 * self.scale
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size.x == 0 || self.size.y == 0
+    }
+}
+
+impl Drop for FontContext {
+    fn drop(&mut self) {
+        self.fonts.clear();
+
+        unsafe {
+            FT_Done_FreeType(self.lib);
+        }
+    }
 }
 
 impl FontContext {
     pub fn build() -> Result<Self, String> {
-        let font = include_bytes!("../../Builtin/Fonts/DejaVuSans.ttf");
-
         let mut lib: FT_Library = ptr::null_mut();
 
-        let mut result = unsafe {
+        let result = unsafe {
             // Initialise la librarie FreeType.
             FT_Init_FreeType(&mut lib)
         };
@@ -38,143 +209,636 @@ impl FontContext {
             return Err("cannot initialize FreeType library".to_string());
         }
 
+        // À partir d'ici, la librairie est libérée par `Drop` en cas d'erreur.
+        let mut font_context = Self {
+            fonts: HashMap::new(),
+            next_font_id: 0,
+            fonts_revision: 0,
+            characteres: RefCell::new(GlyphCache::default()),
+            atlas: RefCell::new(GlyphAtlas::new()),
+            pixel_atlas: RefCell::new(GlyphAtlas::with_filter(TextureFilter::Nearest)),
+            fallbacks: RefCell::new(HashMap::new()),
+            coverage: RefCell::new(HashMap::new()),
+            glyph_mode: GlyphMode::default(),
+            lib,
+        };
+
+        font_context
+            .load_font(
+                DEFAULT_FONT,
+                Cow::Borrowed(include_bytes!("../../Builtin/Fonts/DejaVuSans.ttf")),
+            )
+            .map_err(|_| "cannot load default font face".to_string())?;
+
+        font_context.add_bitmap_font(HEX_FONT, BitmapFont::hex_digits());
+
+        // Les caractères ASCII imprimables sont préparés d'avance, les autres le seront au
+        // premier usage.
+        for c in (32_u8..127).map(char::from) {
+            if let Err(err) = font_context.load_glyph(DEFAULT_FONT, DEFAULT_FONT_SIZE, c) {
+                punk_error!("{err}");
+            }
+        }
+
+        Ok(font_context)
+    }
+
+    // Charge une police TrueType ou OpenType, une police du même nom est remplacée.
+    pub fn load_font_file<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<(), String> {
+        let data = fs::read(path.as_ref())
+            .map_err(|e| format!("cannot read font {}: {e}", path.as_ref().display()))?;
+
+        self.load_font(name, Cow::Owned(data))
+    }
+
+    pub fn load_font_bytes(&mut self, name: &str, data: Vec<u8>) -> Result<(), String> {
+        self.load_font(name, Cow::Owned(data))
+    }
+
+    pub fn load_bdf_file<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<(), String> {
+        let font = BitmapFont::load_bdf_file(path)?;
+
+        self.add_bitmap_font(name, font);
+
+        Ok(())
+    }
+
+    // Une police du même nom est remplacée.
+    pub fn add_bitmap_font(&mut self, name: &str, font: BitmapFont) {
+        self.insert_font(name, FontSource::Bitmap(font));
+    }
+
+    fn load_font(&mut self, name: &str, data: Cow<'static, [u8]>) -> Result<(), String> {
         let mut face: FT_Face = ptr::null_mut();
 
-        result = unsafe {
+        let result = unsafe {
             // Charge une police d'écriture depuis la mémoire.
-            FT_New_Memory_Face(lib, font.as_ptr(), font.len() as i64, 0, &mut face)
+            FT_New_Memory_Face(self.lib, data.as_ptr(), data.len() as i64, 0, &mut face)
         };
 
         if !succeeded(result) {
-            unsafe {
-                FT_Done_FreeType(lib);
-            }
+            return Err(format!("cannot load font face '{name}'"));
+        }
+
+        let font = FontFace {
+            face,
+            _data: data,
+            current_size: Cell::new(0),
+        };
+
+        self.insert_font(name, FontSource::Outline(font));
 
-            return Err("cannot load default font face".to_string());
+        Ok(())
+    }
+
+    // Un nouvel identifiant, afin que les glyphes de l'ancienne police du même nom ne soient
+    // plus utilisés.
+    fn insert_font(&mut self, name: &str, source: FontSource) {
+        let font = Font {
+            id: self.next_font_id,
+            source,
+        };
+
+        self.next_font_id += 1;
+        self.fonts_revision += 1;
+
+        if let Some(previous) = self.fonts.insert(name.to_string(), font) {
+            self.forget_coverage(previous.id);
         }
+    }
 
-        unsafe {
-            // Défini la taille de la police d'écriture.
-            FT_Set_Pixel_Sizes(face, 0, 48);
+    fn forget_coverage(&self, font_id: usize) {
+        self.coverage
+            .borrow_mut()
+            .retain(|(id, _), _| *id != font_id);
+    }
+
+    // Indique si la police contient le caractère, sans le rastériser.
+    pub fn has_glyph(&self, font: &str, c: char) -> Result<bool, String> {
+        let font = self.get_font(font)?;
+
+        if let Some(found) = self.coverage.borrow().get(&(font.id, c)) {
+            return Ok(*found);
         }
 
-        // Génère et stock toutes les textures des caractères afin de ne pas avoir à le faire à
-        // chaque frame.
+        let found = match &font.source {
+            FontSource::Outline(face) => unsafe {
+                FT_Get_Char_Index(face.face, c as FT_ULong) != 0
+            },
+            FontSource::Bitmap(bitmap) => bitmap.has_glyph(c),
+        };
 
-        // Désactive l'alignement des octets dans OpenGL.
-        if let Err(err) = gl_exec!(|| gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1)) {
-            unsafe {
-                FT_Done_Face(face);
-                FT_Done_FreeType(lib);
-            }
+        self.coverage.borrow_mut().insert((font.id, c), found);
+
+        Ok(found)
+    }
 
-            return Err(err);
+    // Index de la première police de la chaîne qui contient le caractère. Sans police qui le
+    // contienne, la première est gardée et affichera une boîte.
+    pub fn resolve_font(&self, fonts: &[String], c: char) -> Result<usize, String> {
+        if fonts.is_empty() {
+            return Err("no font given".to_string());
         }
 
-        let mut characteres = HashMap::new();
+        for (index, font) in fonts.iter().enumerate() {
+            if self.has_glyph(font, c)? {
+                return Ok(index);
+            }
+        }
+
+        Ok(0)
+    }
+
+    pub fn is_bitmap_font(&self, name: &str) -> bool {
+        matches!(
+            self.fonts.get(name).map(|font| &font.source),
+            Some(FontSource::Bitmap(_))
+        )
+    }
+
+    // La police intégrée ne peut pas être retirée.
+    pub fn remove_font(&mut self, name: &str) -> bool {
+        if name == DEFAULT_FONT {
+            return false;
+        }
 
-        for c in 0_u32..128 {
-            // Charge le glyph du caractère.
-            result = unsafe { FT_Load_Char(face, c as FT_ULong, FT_LOAD_RENDER as FT_Int32) };
+        match self.fonts.remove(name) {
+            Some(font) => {
+                self.forget_coverage(font.id);
+                self.fonts_revision += 1;
 
-            if !succeeded(result) {
-                punk_error!("cannot load glyph");
-                continue;
+                true
             }
+            None => false,
+        }
+    }
 
-            // Génère la texture.
-            let mut texture_id = 0;
-            if let Err(err) = gl_exec!(|| gl::GenTextures(1, &mut texture_id)) {
-                punk_error!("{err}");
-                continue;
+    pub fn has_font(&self, name: &str) -> bool {
+        self.fonts.contains_key(name)
+    }
+
+    // Noms des polices chargées, triés.
+    pub fn get_font_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.fonts.keys().cloned().collect();
+
+        names.sort();
+
+        names
+    }
+
+    // Retourne le glyphe du caractère, rastérisé s'il n'est pas encore en cache. Un caractère
+    // absent de la police est remplacé par une boîte.
+    // Remplir l'atlas change la liaison de l'unité active sans passer par le cache d'état, qui
+    // est donc prévenu.
+    pub fn get_glyph(
+        &self,
+        font: &str,
+        size: u32,
+        c: char,
+        state: &GlStateCache,
+    ) -> Result<Charactere, String> {
+        let (raster_size, scale) = self.get_raster_size(font, size)?;
+        let key = self.glyph_key(font, raster_size, c)?;
+
+        if let Some(charactere) = self.characteres.borrow_mut().get(key) {
+            return Ok(charactere.share().with_scale(scale));
+        }
+
+        let charactere = self.load_glyph(font, raster_size, c);
+
+        state.invalidate_textures();
+
+        charactere.map(|charactere| charactere.with_scale(scale))
+    }
+
+    // Taille à laquelle les glyphes sont rastérisés pour être affichés à `size`, et le rapport
+    // entre les deux.
+    fn get_raster_size(&self, font: &str, size: u32) -> Result<(u32, f32), String> {
+        if size == 0 {
+            return Err("font size must be greater than 0".to_string());
+        }
+
+        match (&self.get_font(font)?.source, self.glyph_mode) {
+            (FontSource::Outline(_), GlyphMode::Sdf) => {
+                Ok((SDF_BASE_SIZE, size as f32 / SDF_BASE_SIZE as f32))
             }
+            _ => Ok((size, 1.0_f32)),
+        }
+    }
 
-            // Rend la texture nouvellement générée active.
-            if let Err(err) = gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, texture_id)) {
-                punk_error!("{err}");
-                let _ = gl_exec!(|| gl::DeleteTextures(1, &texture_id));
-                continue;
+    pub fn get_line_metrics(&self, font: &str, size: u32) -> Result<LineMetrics, String> {
+        let font = match &self.get_font(font)?.source {
+            FontSource::Outline(font) => font,
+            FontSource::Bitmap(font) => {
+                let scale = Font::get_bitmap_scale(font, size) as f32;
+
+                return Ok(LineMetrics {
+                    ascender: font.get_ascent() as f32 * scale,
+                    descender: -(font.get_descent() as f32) * scale,
+                    line_height: font.get_pixel_size() as f32 * scale,
+                });
             }
+        };
 
-            let width = unsafe { (*(*face).glyph).bitmap.width };
-            let height = unsafe { (*(*face).glyph).bitmap.rows };
-
-            // Transfère les données du caractères dans la texture actuellement active.
-            if let Err(err) = gl_exec!(|| gl::TexImage2D(
-                gl::TEXTURE_2D,
-                0,
-                gl::RED as GLint,
-                width as GLsizei,
-                height as GLsizei,
-                0,
-                gl::RED,
-                gl::UNSIGNED_BYTE,
-                (*(*face).glyph).bitmap.buffer as *const os::raw::c_void,
-            )) {
-                punk_error!("{err}");
-                let _ = gl_exec!(|| gl::DeleteTextures(1, &texture_id));
-                continue;
+        font.select_size(size)?;
+
+        let metrics = unsafe { (*(*font.face).size).metrics };
+
+        Ok(LineMetrics {
+            ascender: (metrics.ascender >> 6) as f32,
+            descender: (metrics.descender >> 6) as f32,
+            line_height: (metrics.height >> 6) as f32,
+        })
+    }
+
+    // Ajustement horizontal entre deux caractères consécutifs, en pixels.
+    pub fn get_kerning(
+        &self,
+        font: &str,
+        size: u32,
+        left: char,
+        right: char,
+    ) -> Result<f32, String> {
+        // Les polices bitmap sont à chasse fixe ou donnent l'avance de chaque glyphe.
+        let font = match &self.get_font(font)?.source {
+            FontSource::Outline(font) => font,
+            FontSource::Bitmap(_) => return Ok(0.0_f32),
+        };
+
+        if unsafe { (*font.face).face_flags } & FT_FACE_FLAG_KERNING as i64 == 0 {
+            return Ok(0.0_f32);
+        }
+
+        font.select_size(size)?;
+
+        let (left, right) = unsafe {
+            (
+                FT_Get_Char_Index(font.face, left as FT_ULong),
+                FT_Get_Char_Index(font.face, right as FT_ULong),
+            )
+        };
+
+        if left == 0 || right == 0 {
+            return Ok(0.0_f32);
+        }
+
+        let mut kerning = FT_Vector { x: 0, y: 0 };
+
+        let result = unsafe {
+            FT_Get_Kerning(
+                font.face,
+                left,
+                right,
+                FT_Kerning_Mode::FT_KERNING_DEFAULT as u32,
+                &mut kerning,
+            )
+        };
+
+        if !succeeded(result) {
+            return Ok(0.0_f32);
+        }
+
+        Ok(kerning.x as f32 / 64.0_f32)
+    }
+
+    // Tous les glyphes sont abandonnés et seront rastérisés à nouveau dans le nouveau mode.
+    pub fn set_glyph_mode(&mut self, glyph_mode: GlyphMode) {
+        if self.glyph_mode == glyph_mode {
+            return;
+        }
+
+        self.glyph_mode = glyph_mode;
+
+        self.atlas.borrow_mut().clear();
+        self.pixel_atlas.borrow_mut().clear();
+        self.characteres.borrow_mut().clear();
+        self.fallbacks.borrow_mut().clear();
+    }
+
+    pub fn get_glyph_mode(&self) -> GlyphMode {
+        self.glyph_mode
+    }
+
+    // Change lorsque l'atlas est vidé ou qu'une police est chargée ou retirée : les glyphes
+    // obtenus avant peuvent ne plus être valides.
+    pub fn get_generation(&self) -> u64 {
+        self.atlas.borrow().get_generation()
+            + self.pixel_atlas.borrow().get_generation()
+            + self.fonts_revision
+    }
+
+    fn get_font(&self, font: &str) -> Result<&Font, String> {
+        self.fonts
+            .get(font)
+            .ok_or(format!("font '{font}' not found"))
+    }
+
+    fn get_default_face(&self) -> FT_Face {
+        match self.fonts.get(DEFAULT_FONT).map(|font| &font.source) {
+            Some(FontSource::Outline(font)) => font.face,
+            _ => ptr::null_mut(),
+        }
+    }
+
+    fn glyph_key(&self, font: &str, size: u32, c: char) -> Result<GlyphKey, String> {
+        if size == 0 {
+            return Err("font size must be greater than 0".to_string());
+        }
+
+        Ok(GlyphKey {
+            font: self.get_font(font)?.id,
+            size,
+            c,
+        })
+    }
+
+    fn load_glyph(&self, font: &str, size: u32, c: char) -> Result<Charactere, String> {
+        let key = self.glyph_key(font, size, c)?;
+
+        let (charactere, distance_field) = match &self.get_font(font)?.source {
+            FontSource::Outline(font) => (
+                self.rasterize(font, size, c)?,
+                self.glyph_mode == GlyphMode::Sdf,
+            ),
+            FontSource::Bitmap(font) => (self.rasterize_bitmap(font, size, c)?, false),
+        };
+
+        let charactere = match charactere {
+            Some(charactere) => charactere,
+            None => self.get_fallback(size, distance_field)?,
+        };
+
+        let glyph = charactere.share();
+
+        self.characteres.borrow_mut().insert(key, charactere);
+
+        Ok(glyph)
+    }
+
+    // Retourne `None` si la police ne contient pas le caractère ou ne peut pas le rendre.
+    fn rasterize(&self, font: &FontFace, size: u32, c: char) -> Result<Option<Charactere>, String> {
+        let face = font.face;
+
+        if unsafe { FT_Get_Char_Index(face, c as FT_ULong) } == 0 {
+            return Ok(None);
+        }
+
+        font.select_size(size)?;
+
+        let font_size = size;
+
+        // Charge le glyph du caractère.
+        let result = unsafe { FT_Load_Char(face, c as FT_ULong, FT_LOAD_RENDER as FT_Int32) };
+
+        if !succeeded(result) {
+            punk_error!("cannot load glyph for {c} charactere");
+
+            return Ok(None);
+        }
+        let bitmap = unsafe { &(*(*face).glyph).bitmap };
+
+        let size = Vec2 {
+            x: bitmap.width,
+            y: bitmap.rows,
+        };
+
+        // Les lignes peuvent être plus longues que la largeur du glyphe.
+        let pitch = (bitmap.pitch.unsigned_abs()).max(size.x);
+
+        let pixels: &[u8] = if bitmap.buffer.is_null() {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(bitmap.buffer, (pitch * size.y) as usize) }
+        };
+
+        let mut charactere = Charactere {
+            texture: GlObject::none(GlObjectKind::Texture),
+            uv_min: Vec2::default(),
+            uv_max: Vec2::default(),
+            size,
+            bearing: Vec2 {
+                x: unsafe { (*(*face).glyph).bitmap_left },
+                y: unsafe { (*(*face).glyph).bitmap_top },
+            },
+            advance_x: unsafe { (*(*face).glyph).advance.x },
+
+            scale: 1.0_f32,
+            distance_field: false,
+        };
+
+        if charactere.is_empty() {
+            return Ok(Some(charactere));
+        }
+
+        let distance_field = self.glyph_mode == GlyphMode::Sdf;
+
+        let (pitch, pixels) =
+            self.encode(&mut charactere, font_size, pitch, pixels, distance_field);
+
+        match self.pack(&charactere.size, pitch, &pixels, false)? {
+            Some(region) => {
+                charactere.texture = region.texture;
+                charactere.uv_min = region.uv_min;
+                charactere.uv_max = region.uv_max;
+
+                Ok(Some(charactere))
             }
+            // Plus grand qu'une page de l'atlas.
+            None => Ok(None),
+        }
+    }
+
+    // Avec `distance_field`, le bitmap est remplacé par son champ de distance, plus grand de
+    // l'étalement de chaque côté. Retourne le pitch et les pixels à ranger dans l'atlas.
+    fn encode<'a>(
+        &self,
+        charactere: &mut Charactere,
+        font_size: u32,
+        pitch: u32,
+        pixels: &'a [u8],
+        distance_field: bool,
+    ) -> (u32, Cow<'a, [u8]>) {
+        if !distance_field {
+            return (pitch, Cow::Borrowed(pixels));
+        }
+
+        let spread = (font_size / SDF_SPREAD_DIVISOR).max(2);
+
+        let field = sdf::generate_sdf(charactere.size.x, charactere.size.y, pitch, pixels, spread);
+
+        charactere.size.x += spread * 2;
+        charactere.size.y += spread * 2;
+        charactere.bearing.x -= spread as i32;
+        charactere.bearing.y += spread as i32;
+        charactere.distance_field = true;
 
-            // Défini les options de la texture.
-            let _ = gl_exec!(|| gl::TexParameteri(
-                gl::TEXTURE_2D,
-                gl::TEXTURE_WRAP_S,
-                gl::CLAMP_TO_EDGE as GLint
-            ));
-            let _ = gl_exec!(|| gl::TexParameteri(
-                gl::TEXTURE_2D,
-                gl::TEXTURE_WRAP_T,
-                gl::CLAMP_TO_EDGE as GLint
-            ));
-            let _ = gl_exec!(|| gl::TexParameteri(
-                gl::TEXTURE_2D,
-                gl::TEXTURE_MIN_FILTER,
-                gl::LINEAR as GLint
-            ));
-            let _ = gl_exec!(|| gl::TexParameteri(
-                gl::TEXTURE_2D,
-                gl::TEXTURE_MAG_FILTER,
-                gl::LINEAR as GLint
-            ));
-
-            let charactere = Charactere {
-                texture_id,
-                size: Vec2 {
-                    x: width,
-                    y: height,
-                },
-                bearing: Vec2 {
-                    x: unsafe { (*(*face).glyph).bitmap_left },
-                    y: unsafe { (*(*face).glyph).bitmap_top },
-                },
-                advance_x: unsafe { (*(*face).glyph).advance.x },
-            };
-
-            characteres.insert(char::from_u32(c).unwrap(), charactere);
-        }
-
-        // Réactive l'alignement des octets dans OpenGL.
-        if let Err(err) = gl_exec!(|| gl::PixelStorei(gl::PACK_ALIGNMENT, 1)) {
-            unsafe {
-                FT_Done_Face(face);
-                FT_Done_FreeType(lib);
+        (charactere.size.x, Cow::Owned(field))
+    }
+
+    // Agrandit le glyphe d'un nombre entier de fois afin que ses pixels restent carrés et
+    // alignés, sans passer par le mode SDF.
+    fn rasterize_bitmap(
+        &self,
+        font: &BitmapFont,
+        size: u32,
+        c: char,
+    ) -> Result<Option<Charactere>, String> {
+        let glyph = match font.get_glyph(c) {
+            Some(glyph) => glyph,
+            None => return Ok(None),
+        };
+
+        let scale = Font::get_bitmap_scale(font, size);
+
+        let mut charactere = Charactere {
+            texture: GlObject::none(GlObjectKind::Texture),
+            uv_min: Vec2::default(),
+            uv_max: Vec2::default(),
+            size: Vec2 {
+                x: glyph.size.x * scale,
+                y: glyph.size.y * scale,
+            },
+            bearing: Vec2 {
+                x: glyph.bearing.x * scale as i32,
+                y: glyph.bearing.y * scale as i32,
+            },
+            advance_x: ((glyph.advance * scale) << 6) as i64,
+
+            scale: 1.0_f32,
+            distance_field: false,
+        };
+
+        if charactere.is_empty() {
+            return Ok(Some(charactere));
+        }
+
+        let pixels: Vec<u8> = (0..charactere.size.y)
+            .flat_map(|y| (0..charactere.size.x).map(move |x| (x / scale, y / scale)))
+            .map(|(x, y)| {
+                glyph
+                    .pixels
+                    .get((y * glyph.size.x + x) as usize)
+                    .copied()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        match self.pack(&charactere.size, charactere.size.x, &pixels, true)? {
+            Some(region) => {
+                charactere.texture = region.texture;
+                charactere.uv_min = region.uv_min;
+                charactere.uv_max = region.uv_max;
+
+                Ok(Some(charactere))
             }
+            None => Ok(None),
+        }
+    }
+
+    // Range le bitmap dans l'atlas, sans filtrage pour les polices bitmap. Lorsqu'il est plein,
+    // il est entièrement vidé : les glyphes en cache sont abandonnés et seront rastérisés à
+    // nouveau au prochain usage.
+    fn pack(
+        &self,
+        size: &Vec2<u32>,
+        pitch: u32,
+        pixels: &[u8],
+        pixel_exact: bool,
+    ) -> Result<Option<AtlasRegion>, String> {
+        let mut atlas = if pixel_exact {
+            self.pixel_atlas.borrow_mut()
+        } else {
+            self.atlas.borrow_mut()
+        };
 
-            return Err(err);
+        if let Some(region) = atlas.insert(size.x, size.y, pitch, pixels)? {
+            return Ok(Some(region));
         }
 
-        Ok(Self {
-            lib,
-            face_font: face,
-            characteres,
-        })
+        atlas.clear();
+        self.characteres.borrow_mut().clear();
+        self.fallbacks.borrow_mut().clear();
+
+        atlas.insert(size.x, size.y, pitch, pixels)
+    }
+
+    // Rectangle vide aux proportions d'une lettre majuscule, encodé comme les glyphes de la
+    // police qu'il remplace.
+    fn get_fallback(&self, font_size: u32, distance_field: bool) -> Result<Charactere, String> {
+        if let Some(fallback) = self.fallbacks.borrow().get(&(font_size, distance_field)) {
+            return Ok(fallback.share());
+        }
+
+        let width = (font_size / 2).max(3);
+        let height = (font_size * 3 / 4).max(3);
+        let border = (font_size / 24).max(1);
+
+        let pixels: Vec<u8> = (0..height)
+            .flat_map(|y| {
+                (0..width).map(move |x| {
+                    let edge =
+                        x < border || y < border || x >= width - border || y >= height - border;
+
+                    if edge {
+                        255
+                    } else {
+                        0
+                    }
+                })
+            })
+            .collect();
+
+        let mut fallback = Charactere {
+            texture: GlObject::none(GlObjectKind::Texture),
+            uv_min: Vec2::default(),
+            uv_max: Vec2::default(),
+            size: Vec2 {
+                x: width,
+                y: height,
+            },
+            bearing: Vec2 {
+                x: border as i32,
+                y: height as i32,
+            },
+            advance_x: ((width + border * 2) << 6) as i64,
+
+            scale: 1.0_f32,
+            distance_field: false,
+        };
+
+        let (pitch, pixels) = self.encode(&mut fallback, font_size, width, &pixels, distance_field);
+
+        let region = self
+            .pack(&fallback.size, pitch, &pixels, false)?
+            .ok_or("cannot fit the fallback glyph in the atlas".to_string())?;
+
+        fallback.texture = region.texture;
+        fallback.uv_min = region.uv_min;
+        fallback.uv_max = region.uv_max;
+
+        let glyph = fallback.share();
+
+        self.fallbacks
+            .borrow_mut()
+            .insert((font_size, distance_field), fallback);
+
+        Ok(glyph)
+    }
+
+    // Nombre maximum de glyphes gardés dans le cache. Leur place dans l'atlas n'est rendue que
+    // lorsqu'il est vidé.
+    pub fn set_glyph_cache_capacity(&self, capacity: usize) {
+        self.characteres.borrow_mut().set_capacity(capacity);
+    }
+
+    pub fn get_cached_glyph_count(&self) -> usize {
+        self.characteres.borrow().len()
     }
 
     pub fn set_active_glyph(&mut self, charactere: char) -> Result<(), String> {
         let result = unsafe {
             FT_Load_Char(
-                self.face_font,
+                self.get_default_face(),
                 charactere as FT_ULong,
                 FT_LOAD_RENDER as FT_Int32,
             )
@@ -188,26 +852,26 @@ impl FontContext {
     }
 
     pub fn get_active_glyph_width(&self) -> u32 {
-        unsafe { (*(*self.face_font).glyph).bitmap.width }
+        unsafe { (*(*self.get_default_face()).glyph).bitmap.width }
     }
 
     pub fn get_active_glyph_height(&self) -> u32 {
-        unsafe { (*(*self.face_font).glyph).bitmap.rows }
+        unsafe { (*(*self.get_default_face()).glyph).bitmap.rows }
     }
 
     pub fn get_active_glyph_bearing_x(&self) -> i32 {
-        unsafe { (*(*self.face_font).glyph).bitmap_left }
+        unsafe { (*(*self.get_default_face()).glyph).bitmap_left }
     }
 
     pub fn get_active_glyph_bearing_y(&self) -> i32 {
-        unsafe { (*(*self.face_font).glyph).bitmap_top }
+        unsafe { (*(*self.get_default_face()).glyph).bitmap_top }
     }
 
     pub fn get_active_glyph_advance_x(&self) -> i64 {
-        unsafe { (*(*self.face_font).glyph).advance.x }
+        unsafe { (*(*self.get_default_face()).glyph).advance.x }
     }
 
     pub fn get_active_glyph_advance_y(&self) -> i64 {
-        unsafe { (*(*self.face_font).glyph).advance.y }
+        unsafe { (*(*self.get_default_face()).glyph).advance.y }
     }
 }
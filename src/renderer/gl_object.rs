@@ -0,0 +1,121 @@
+use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+
+use gl::types::GLuint;
+
+use crate::{gl_exec, punk_warning};
+
+use super::debug;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GlObjectKind {
+    VertexArray,
+    Buffer,
+    Shader,
+    Program,
+    Texture,
+    Framebuffer,
+    Renderbuffer,
+    Query,
+}
+
+thread_local! {
+    // Objets OpenGL encore vivants, utilisé pour le rapport de fuites à la fermeture.
+    static LIVE_OBJECTS: RefCell<BTreeSet<(GlObjectKind, GLuint)>> =
+        const { RefCell::new(BTreeSet::new()) };
+}
+
+struct OwnedObject {
+    kind: GlObjectKind,
+    id: GLuint,
+}
+
+impl Drop for OwnedObject {
+    fn drop(&mut self) {
+        if self.id == 0 {
+            return;
+        }
+
+        let id = self.id;
+
+        // Une erreur ne peut pas être remontée depuis `drop`.
+        let _ = match self.kind {
+            GlObjectKind::VertexArray => gl_exec!(|| gl::DeleteVertexArrays(1, &id)),
+            GlObjectKind::Buffer => gl_exec!(|| gl::DeleteBuffers(1, &id)),
+            GlObjectKind::Shader => gl_exec!(|| gl::DeleteShader(id)),
+            GlObjectKind::Program => gl_exec!(|| gl::DeleteProgram(id)),
+            GlObjectKind::Texture => gl_exec!(|| gl::DeleteTextures(1, &id)),
+            GlObjectKind::Framebuffer => gl_exec!(|| gl::DeleteFramebuffers(1, &id)),
+            GlObjectKind::Renderbuffer => gl_exec!(|| gl::DeleteRenderbuffers(1, &id)),
+            GlObjectKind::Query => gl_exec!(|| gl::DeleteQueries(1, &id)),
+        };
+
+        let _ = LIVE_OBJECTS.try_with(|live| live.borrow_mut().remove(&(self.kind, id)));
+    }
+}
+
+// Poignée comptée sur un objet OpenGL, l'objet est supprimé lorsque la dernière poignée est
+// libérée. Une copie ne se fait qu'explicitement avec `share`.
+pub struct GlObject {
+    inner: Rc<OwnedObject>,
+}
+
+impl GlObject {
+    // Prend possession d'un objet déjà généré.
+    pub fn new(kind: GlObjectKind, id: GLuint) -> Self {
+        if id != 0 {
+            LIVE_OBJECTS.with(|live| live.borrow_mut().insert((kind, id)));
+        }
+
+        Self {
+            inner: Rc::new(OwnedObject { kind, id }),
+        }
+    }
+
+    // Poignée vide, qui ne supprime rien.
+    pub fn none(kind: GlObjectKind) -> Self {
+        Self::new(kind, 0)
+    }
+
+    pub fn share(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+
+    pub fn get_id(&self) -> GLuint {
+        self.inner.id
+    }
+
+    pub fn get_kind(&self) -> GlObjectKind {
+        self.inner.kind
+    }
+
+    pub fn set_label(&self, label: &str) {
+        debug::set_object_label(self.inner.kind, self.inner.id, label);
+    }
+
+    pub fn get_owner_count(&self) -> usize {
+        Rc::strong_count(&self.inner)
+    }
+}
+
+pub fn live_objects() -> Vec<(GlObjectKind, GLuint)> {
+    LIVE_OBJECTS.with(|live| live.borrow().iter().copied().collect())
+}
+
+// Affiche les objets qui n'ont pas été libérés et retourne leur nombre.
+pub fn report_leaks() -> usize {
+    let objects = live_objects();
+
+    if objects.is_empty() {
+        return 0;
+    }
+
+    punk_warning!("{} OpenGL object(s) still alive at shutdown", objects.len());
+
+    for (kind, id) in objects.iter() {
+        punk_warning!("  {kind:?} {id}");
+    }
+
+    objects.len()
+}
@@ -0,0 +1,201 @@
+use std::os;
+
+use gl::types::{GLint, GLsizei};
+
+use crate::{gl_exec, maths::vec::Vec2};
+
+use super::{
+    gl_object::{GlObject, GlObjectKind},
+    rect_packer::RectPacker,
+    state_cache::{self, DirectBinding},
+    stats,
+    texture::TextureFilter,
+};
+
+const ATLAS_PAGE_SIZE: u32 = 1024;
+const MAX_ATLAS_PAGES: usize = 4;
+// Espace laissé autour de chaque glyphe afin que le filtrage linéaire ne lise pas ses voisins.
+const GLYPH_PADDING: u32 = 1;
+
+// Emplacement d'un bitmap dans une page de l'atlas.
+pub struct AtlasRegion {
+    pub(crate) texture: GlObject,
+    pub(crate) uv_min: Vec2<f32>,
+    pub(crate) uv_max: Vec2<f32>,
+}
+
+struct AtlasPage {
+    texture: GlObject,
+    packer: RectPacker,
+}
+
+// Textures à un canal (rouge) dans lesquelles les glyphes sont rangés, afin qu'un texte entier
+// se dessine avec une seule texture.
+pub struct GlyphAtlas {
+    pages: Vec<AtlasPage>,
+    filter: TextureFilter,
+    // Incrémentée à chaque remise à zéro : les coordonnées obtenues avant ne sont plus valides.
+    generation: u64,
+}
+
+impl Default for GlyphAtlas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlyphAtlas {
+    pub fn new() -> Self {
+        Self::with_filter(TextureFilter::Linear)
+    }
+
+    // `TextureFilter::Nearest` garde les pixels des polices bitmap nets une fois agrandis.
+    pub fn with_filter(filter: TextureFilter) -> Self {
+        Self {
+            pages: Vec::new(),
+            filter,
+            generation: 0,
+        }
+    }
+
+    // `pixels` contient `height` lignes de `pitch` octets. Retourne `None` si toutes les pages
+    // sont pleines ou si le bitmap est plus grand qu'une page.
+    // La texture de la page reste liée sur l'unité active.
+    pub fn insert(
+        &mut self,
+        width: u32,
+        height: u32,
+        pitch: u32,
+        pixels: &[u8],
+    ) -> Result<Option<AtlasRegion>, String> {
+        if pixels.len() < (pitch * height) as usize || pitch < width {
+            return Err("glyph bitmap is smaller than its size".to_string());
+        }
+
+        let padded = (width + GLYPH_PADDING, height + GLYPH_PADDING);
+
+        let mut found = None;
+
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            if let Some(position) = page.packer.insert(padded.0, padded.1) {
+                found = Some((index, position));
+                break;
+            }
+        }
+
+        if found.is_none() && self.pages.len() < MAX_ATLAS_PAGES {
+            let mut page = Self::build_page(self.pages.len(), self.filter)?;
+
+            found = page
+                .packer
+                .insert(padded.0, padded.1)
+                .map(|position| (self.pages.len(), position));
+
+            self.pages.push(page);
+        }
+
+        let (index, (x, y)) = match found {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let texture = &self.pages[index].texture;
+
+        gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, texture.get_id()))?;
+        state_cache::record_direct_binding(DirectBinding::Texture);
+
+        // Les lignes d'un bitmap FreeType ne sont pas alignées sur 4 octets.
+        gl_exec!(|| gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1))?;
+        gl_exec!(|| gl::PixelStorei(gl::UNPACK_ROW_LENGTH, pitch as GLint))?;
+
+        gl_exec!(|| gl::TexSubImage2D(
+            gl::TEXTURE_2D,
+            0,
+            x as GLint,
+            y as GLint,
+            width as GLsizei,
+            height as GLsizei,
+            gl::RED,
+            gl::UNSIGNED_BYTE,
+            pixels.as_ptr() as *const os::raw::c_void,
+        ))?;
+
+        gl_exec!(|| gl::PixelStorei(gl::UNPACK_ROW_LENGTH, 0))?;
+
+        stats::record_texture_upload();
+
+        let size = ATLAS_PAGE_SIZE as f32;
+
+        Ok(Some(AtlasRegion {
+            texture: texture.share(),
+            uv_min: Vec2 {
+                x: x as f32 / size,
+                y: y as f32 / size,
+            },
+            uv_max: Vec2 {
+                x: (x + width) as f32 / size,
+                y: (y + height) as f32 / size,
+            },
+        }))
+    }
+
+    // Libère la place de tous les glyphes, les textures sont gardées.
+    pub fn clear(&mut self) {
+        for page in self.pages.iter_mut() {
+            page.packer.clear();
+        }
+
+        self.generation += 1;
+    }
+
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get_page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    fn build_page(index: usize, filter: TextureFilter) -> Result<AtlasPage, String> {
+        let mut texture_id = 0;
+
+        gl_exec!(|| gl::GenTextures(1, &mut texture_id))?;
+
+        let texture = GlObject::new(GlObjectKind::Texture, texture_id);
+
+        gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, texture_id))?;
+        state_cache::record_direct_binding(DirectBinding::Texture);
+
+        // Remplie de zéros afin que les marges entre les glyphes soient transparentes.
+        let pixels = vec![0_u8; (ATLAS_PAGE_SIZE * ATLAS_PAGE_SIZE) as usize];
+
+        gl_exec!(|| gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1))?;
+        gl_exec!(|| gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::R8 as GLint,
+            ATLAS_PAGE_SIZE as GLsizei,
+            ATLAS_PAGE_SIZE as GLsizei,
+            0,
+            gl::RED,
+            gl::UNSIGNED_BYTE,
+            pixels.as_ptr() as *const os::raw::c_void,
+        ))?;
+
+        for (parameter, value) in [
+            (gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE),
+            (gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE),
+            (gl::TEXTURE_MIN_FILTER, filter.get_gl_filter()),
+            (gl::TEXTURE_MAG_FILTER, filter.get_gl_filter()),
+        ] {
+            gl_exec!(|| gl::TexParameteri(gl::TEXTURE_2D, parameter, value as GLint))?;
+        }
+
+        texture.set_label(&format!("punk_glyph_atlas {index}"));
+
+        Ok(AtlasPage {
+            texture,
+            packer: RectPacker::new(ATLAS_PAGE_SIZE, ATLAS_PAGE_SIZE),
+        })
+    }
+}
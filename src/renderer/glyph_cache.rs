@@ -0,0 +1,153 @@
+use std::{collections::HashMap, hash::Hash};
+
+// Nombre de glyphes gardés par défaut, au-delà les moins récemment utilisés sont abandonnés.
+pub const DEFAULT_GLYPH_CACHE_CAPACITY: usize = 1024;
+
+struct Entry<T> {
+    value: T,
+    last_used: u64,
+}
+
+// Cache de glyphes avec une éviction LRU, la clé identifie au moins le caractère. Abandonner un
+// glyphe ne rend pas sa place dans l'atlas : la mémoire vidéo n'est bornée que par le nombre de
+// pages de l'atlas, entièrement vidé lorsqu'il est plein.
+pub struct GlyphCache<K, T> {
+    entries: HashMap<K, Entry<T>>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl<K: Eq + Hash + Copy, T> Default for GlyphCache<K, T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_GLYPH_CACHE_CAPACITY)
+    }
+}
+
+impl<K: Eq + Hash + Copy, T> GlyphCache<K, T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+        }
+    }
+
+    // Marque le glyphe comme utilisé.
+    pub fn get(&mut self, key: K) -> Option<&T> {
+        self.clock += 1;
+
+        let clock = self.clock;
+
+        self.entries.get_mut(&key).map(|entry| {
+            entry.last_used = clock;
+
+            &entry.value
+        })
+    }
+
+    pub fn contains(&self, key: K) -> bool {
+        self.entries.contains_key(&key)
+    }
+
+    // Retourne les glyphes abandonnés pour faire de la place.
+    pub fn insert(&mut self, key: K, value: T) -> Vec<T> {
+        self.clock += 1;
+
+        self.entries.insert(
+            key,
+            Entry {
+                value,
+                last_used: self.clock,
+            },
+        );
+
+        self.evict()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<T> {
+        self.capacity = capacity.max(1);
+
+        self.evict()
+    }
+
+    pub fn clear(&mut self) -> Vec<T> {
+        self.entries.drain().map(|(_, entry)| entry.value).collect()
+    }
+
+    fn evict(&mut self) -> Vec<T> {
+        let mut evicted = Vec::new();
+
+        while self.entries.len() > self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key);
+
+            match oldest.and_then(|key| self.entries.remove(&key)) {
+                Some(entry) => evicted.push(entry.value),
+                None => break,
+            }
+        }
+
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let mut cache = GlyphCache::new(2);
+
+        assert!(cache.insert('a', 1).is_empty());
+        assert!(cache.insert('b', 2).is_empty());
+
+        // `a` devient plus récent que `b`.
+        assert_eq!(cache.get('a'), Some(&1));
+
+        assert_eq!(cache.insert('c', 3), vec![2]);
+        assert!(cache.contains('a'));
+        assert!(!cache.contains('b'));
+        assert!(cache.contains('c'));
+
+        assert_eq!(cache.insert('d', 4), vec![1]);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_shrink_capacity() {
+        let mut cache = GlyphCache::new(4);
+
+        for (value, key) in ['a', 'b', 'c', 'd'].into_iter().enumerate() {
+            cache.insert(key, value);
+        }
+
+        cache.get('a');
+
+        let mut evicted = cache.set_capacity(2);
+        evicted.sort();
+
+        assert_eq!(evicted, vec![1, 2]);
+        assert_eq!(cache.get_capacity(), 2);
+        assert!(cache.contains('a'));
+        assert!(cache.contains('d'));
+
+        assert_eq!(cache.set_capacity(0).len(), 1);
+        assert_eq!(cache.get_capacity(), 1);
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use gl::types::{GLint, GLuint64};
+
+use crate::gl_exec;
+
+use super::gl_object::{GlObject, GlObjectKind};
+
+// Au-delà, les mesures les plus anciennes sont abandonnées : le GPU a trop de retard ou les
+// résultats ne sont jamais lus.
+const MAX_PENDING_QUERIES: usize = 8;
+
+// Mesure le temps passé par le GPU sur des passes nommées avec des requêtes `GL_TIME_ELAPSED`.
+// Les résultats arrivent quelques frames plus tard, ils sont relevés sans attendre le GPU.
+pub struct GpuTimer {
+    pending: HashMap<String, VecDeque<GlObject>>,
+    free: Vec<GlObject>,
+    active: Option<(String, GlObject)>,
+    enabled: bool,
+}
+
+impl Default for GpuTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GpuTimer {
+    pub fn new() -> Self {
+        Self {
+            pending: HashMap::new(),
+            free: Vec::new(),
+            active: None,
+            enabled: gl::BeginQuery::is_loaded(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, value: bool) {
+        self.enabled = value && gl::BeginQuery::is_loaded();
+    }
+
+    // Les requêtes de temps ne peuvent pas être imbriquées, une passe active est donc terminée
+    // avant de commencer la suivante.
+    pub fn begin(&mut self, name: &str) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        self.end()?;
+
+        let query = match self.free.pop() {
+            Some(query) => query,
+            None => {
+                let mut id = 0;
+
+                gl_exec!(|| gl::GenQueries(1, &mut id))?;
+
+                GlObject::new(GlObjectKind::Query, id)
+            }
+        };
+
+        gl_exec!(|| gl::BeginQuery(gl::TIME_ELAPSED, query.get_id()))?;
+
+        self.active = Some((name.to_string(), query));
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), String> {
+        let (name, query) = match self.active.take() {
+            Some(active) => active,
+            None => return Ok(()),
+        };
+
+        gl_exec!(|| gl::EndQuery(gl::TIME_ELAPSED))?;
+
+        let pending = self.pending.entry(name).or_default();
+
+        pending.push_back(query);
+
+        if pending.len() > MAX_PENDING_QUERIES {
+            if let Some(query) = pending.pop_front() {
+                self.free.push(query);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Retourne les mesures arrivées depuis le dernier appel, une par passe et par frame.
+    pub fn collect(&mut self) -> Result<Vec<(String, Duration)>, String> {
+        let mut results = Vec::new();
+
+        for (name, pending) in self.pending.iter_mut() {
+            while let Some(query) = pending.front() {
+                let mut available: GLint = 0;
+
+                gl_exec!(|| gl::GetQueryObjectiv(
+                    query.get_id(),
+                    gl::QUERY_RESULT_AVAILABLE,
+                    &mut available
+                ))?;
+
+                if available == 0 {
+                    break;
+                }
+
+                let mut elapsed: GLuint64 = 0;
+
+                gl_exec!(|| gl::GetQueryObjectui64v(
+                    query.get_id(),
+                    gl::QUERY_RESULT,
+                    &mut elapsed
+                ))?;
+
+                results.push((name.clone(), Duration::from_nanos(elapsed)));
+
+                if let Some(query) = pending.pop_front() {
+                    self.free.push(query);
+                }
+            }
+        }
+
+        Ok(results)
+    }
+}
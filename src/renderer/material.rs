@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use crate::shader::program::ShaderProgram;
+
+use super::{
+    state_cache::GlStateCache,
+    uniform::{Uniform, UniformValue},
+};
+
+// Variables remplies automatiquement par le moteur lorsqu'elles existent dans le programme.
+pub const PUNK_BUILTINS: [&str; 8] = [
+    "punk_model",
+    "punk_projection",
+    "punk_color",
+    "punk_texture",
+    "punk_texture_color",
+    "punk_resolution",
+    "punk_source_size",
+    "punk_time",
+];
+
+// Associe un programme à des paramètres nommés, envoyés à chaque rendu.
+// Les emplacements des variables sont lus dans la réflexion du programme au moment du rendu,
+// ils restent donc valides après un rechargement à chaud.
+#[derive(Clone)]
+pub struct Material {
+    shader_program: ShaderProgram,
+    params: HashMap<String, UniformValue>,
+}
+
+impl Material {
+    // Sans programme, l'objet ne peut pas être dessiné tant qu'un matériau n'est pas donné.
+    pub fn none() -> Self {
+        Self {
+            shader_program: ShaderProgram::none(),
+            params: HashMap::new(),
+        }
+    }
+
+    pub fn build(shader_program: ShaderProgram) -> Result<Self, String> {
+        Ok(Self {
+            shader_program,
+            params: HashMap::new(),
+        })
+    }
+
+    // Échoue si la variable n'est pas active dans le programme ou si son type GLSL ne
+    // correspond pas à la valeur.
+    pub fn set_param(&mut self, name: &str, value: UniformValue) -> Result<(), String> {
+        let variable = self.shader_program.get_uniform(name)?;
+
+        Uniform::from_variable(&variable).check_value(name, &value)?;
+
+        self.params.insert(name.to_string(), value);
+
+        Ok(())
+    }
+
+    pub fn get_param(&self, name: &str) -> Option<&UniformValue> {
+        self.params.get(name)
+    }
+
+    pub fn remove_param(&mut self, name: &str) -> Option<UniformValue> {
+        self.params.remove(name)
+    }
+
+    // Indique si le programme utilise la variable, par exemple un `punk_*` optionnel.
+    pub fn has_uniform(&self, name: &str) -> bool {
+        self.shader_program.borrow_reflection().has_uniform(name)
+    }
+
+    // Active le programme puis envoie les variables du moteur et les paramètres.
+    // Les textures sont liées aux unités 0, 1, 2... dans l'ordre d'envoi, celles du moteur en
+    // premier.
+    pub fn apply(
+        &self,
+        state: &GlStateCache,
+        builtins: &[(&str, UniformValue)],
+    ) -> Result<(), String> {
+        state.use_program(self.shader_program.get_id())?;
+
+        let reflection = self.shader_program.borrow_reflection();
+        let mut texture_unit = 0;
+
+        let params = self
+            .params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value));
+
+        let builtins = builtins
+            .iter()
+            .filter(|(name, _)| !self.params.contains_key(*name))
+            .map(|(name, value)| (*name, value));
+
+        for (name, value) in builtins.chain(params) {
+            let uniform = match reflection.get_uniform(name) {
+                Some(variable) => Uniform::from_variable(variable),
+                None => continue,
+            };
+
+            // Un paramètre peut ne plus correspondre après le rechargement du programme.
+            uniform.check_value(name, value)?;
+
+            if let UniformValue::Texture(id) = value {
+                state.bind_texture(texture_unit, *id)?;
+            }
+
+            uniform.send_value(value, texture_unit)?;
+
+            if let UniformValue::Texture(_) = value {
+                texture_unit += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn borrow_shader_program(&self) -> &ShaderProgram {
+        &self.shader_program
+    }
+}
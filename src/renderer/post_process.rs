@@ -0,0 +1,372 @@
+use std::{mem, time::Instant};
+
+use gl::types::GLsizei;
+
+use crate::{
+    gl_exec,
+    maths::vec::Vec2,
+    shader::{program::ShaderProgram, registry::ShaderSource},
+};
+
+use super::{
+    blend::BlendMode,
+    material::Material,
+    render_target::RenderTarget,
+    state_cache::GlStateCache,
+    stats,
+    texture::{Texture, TextureFilter},
+    uniform::UniformValue,
+    vao::VAO,
+    vbo::{VBOType, VBO},
+};
+
+pub struct PostProcessPass {
+    name: String,
+    material: Material,
+    enabled: bool,
+    // Textures lues par la passe, gardées en vie aussi longtemps qu'elle.
+    textures: Vec<Texture>,
+}
+
+impl PostProcessPass {
+    // Les passes reçoivent l'image précédente dans `punk_texture` ainsi que `punk_resolution`
+    // (taille de la sortie en pixels), `punk_source_size` (taille du rendu logique) et
+    // `punk_time` (secondes écoulées).
+    pub fn build(name: &str, fragment_source: &str) -> Result<Self, String> {
+        let program = ShaderProgram::from_sources(
+            "post_process_vertex_shader",
+            include_str!("../../Builtin/Shaders/post_process_vertex_shader.glsl"),
+            name,
+            fragment_source,
+        )?;
+
+        Self::from_program(name, program)
+    }
+
+    // Utilise un programme déjà compilé, par exemple chargé depuis un fichier par le registre de
+    // shaders avec `vertex_source` afin de pouvoir le modifier sans recompiler le projet.
+    pub fn from_program(name: &str, program: ShaderProgram) -> Result<Self, String> {
+        Ok(Self {
+            name: name.to_string(),
+            material: Material::build(program)?,
+            enabled: true,
+            textures: Vec::new(),
+        })
+    }
+
+    pub fn vertex_source() -> ShaderSource {
+        ShaderSource::embedded(
+            "post_process_vertex_shader",
+            include_str!("../../Builtin/Shaders/post_process_vertex_shader.glsl"),
+        )
+    }
+
+    pub fn crt_curvature() -> Result<Self, String> {
+        let mut pass = Self::build(
+            "crt_curvature",
+            include_str!("../../Builtin/Shaders/post_process_crt_curvature_fragment_shader.glsl"),
+        )?;
+
+        pass.set_param(
+            "curvature",
+            UniformValue::Vec2(Vec2 {
+                x: 4.0_f32,
+                y: 4.0_f32,
+            }),
+        )?;
+        pass.set_param("vignette", UniformValue::Float(0.25_f32))?;
+
+        Ok(pass)
+    }
+
+    pub fn scanlines() -> Result<Self, String> {
+        let mut pass = Self::build(
+            "scanlines",
+            include_str!("../../Builtin/Shaders/post_process_scanlines_fragment_shader.glsl"),
+        )?;
+
+        pass.set_param("intensity", UniformValue::Float(0.35_f32))?;
+
+        Ok(pass)
+    }
+
+    pub fn shadow_mask() -> Result<Self, String> {
+        let mut pass = Self::build(
+            "shadow_mask",
+            include_str!("../../Builtin/Shaders/post_process_shadow_mask_fragment_shader.glsl"),
+        )?;
+
+        pass.set_param("mask_size", UniformValue::Float(1.0_f32))?;
+        pass.set_param("intensity", UniformValue::Float(0.25_f32))?;
+
+        Ok(pass)
+    }
+
+    pub fn bloom() -> Result<Self, String> {
+        let mut pass = Self::build(
+            "bloom",
+            include_str!("../../Builtin/Shaders/post_process_bloom_fragment_shader.glsl"),
+        )?;
+
+        pass.set_param("threshold", UniformValue::Float(0.7_f32))?;
+        pass.set_param("radius", UniformValue::Float(2.0_f32))?;
+        pass.set_param("intensity", UniformValue::Float(0.8_f32))?;
+
+        Ok(pass)
+    }
+
+    // `lut_size` est le nombre de niveaux par composante, voir `build_identity_lut`. La passe
+    // garde la texture, une copie partagée suffit si l'appelant en a encore besoin.
+    pub fn color_lut(lut: Texture, lut_size: i32) -> Result<Self, String> {
+        let mut pass = Self::build(
+            "color_lut",
+            include_str!("../../Builtin/Shaders/post_process_color_lut_fragment_shader.glsl"),
+        )?;
+
+        pass.set_param("lut", UniformValue::Texture(lut.get_id()))?;
+        pass.set_param("lut_size", UniformValue::Float(lut_size as f32))?;
+        pass.set_param("intensity", UniformValue::Float(1.0_f32))?;
+
+        pass.textures.push(lut);
+
+        Ok(pass)
+    }
+
+    pub fn set_param(&mut self, name: &str, value: UniformValue) -> Result<(), String> {
+        self.material.set_param(name, value)
+    }
+
+    pub fn get_param(&self, name: &str) -> Option<&UniformValue> {
+        self.material.get_param(name)
+    }
+
+    pub fn borrow_material(&self) -> &Material {
+        &self.material
+    }
+
+    pub fn borrow_material_mut(&mut self) -> &mut Material {
+        &mut self.material
+    }
+
+    pub fn borrow_name(&self) -> &String {
+        &self.name
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, value: bool) {
+        self.enabled = value;
+    }
+
+    fn send_uniforms(
+        &self,
+        state: &GlStateCache,
+        source: &RenderTarget,
+        resolution: &Vec2<i32>,
+        source_size: &Vec2<i32>,
+        time: f32,
+    ) -> Result<(), String> {
+        // L'image précédente est envoyée en premier, elle occupe donc l'unité 0.
+        self.material.apply(
+            state,
+            &[
+                (
+                    "punk_texture",
+                    UniformValue::Texture(source.borrow_texture().get_id()),
+                ),
+                (
+                    "punk_resolution",
+                    UniformValue::Vec2(Vec2 {
+                        x: resolution.x as f32,
+                        y: resolution.y as f32,
+                    }),
+                ),
+                (
+                    "punk_source_size",
+                    UniformValue::Vec2(Vec2 {
+                        x: source_size.x as f32,
+                        y: source_size.y as f32,
+                    }),
+                ),
+                ("punk_time", UniformValue::Float(time)),
+            ],
+        )
+    }
+}
+
+pub struct PostProcessChain {
+    passes: Vec<PostProcessPass>,
+    targets: Vec<RenderTarget>,
+    vao: Option<(VAO, VBO)>,
+    start: Instant,
+}
+
+impl Default for PostProcessChain {
+    fn default() -> Self {
+        Self {
+            passes: Vec::new(),
+            targets: Vec::new(),
+            vao: None,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl PostProcessChain {
+    pub fn add_pass(&mut self, pass: PostProcessPass) {
+        self.passes.push(pass);
+    }
+
+    pub fn remove_pass(&mut self, name: &str) -> Option<PostProcessPass> {
+        let index = self.passes.iter().position(|pass| pass.name == name)?;
+
+        Some(self.passes.remove(index))
+    }
+
+    pub fn borrow_pass_mut(&mut self, name: &str) -> Option<&mut PostProcessPass> {
+        self.passes.iter_mut().find(|pass| pass.name == name)
+    }
+
+    pub fn borrow_passes(&self) -> &Vec<PostProcessPass> {
+        &self.passes
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.passes.iter().any(|pass| pass.enabled)
+    }
+
+    // Redirige le rendu de la scène vers une cible hors écran de la taille de la fenêtre.
+    pub fn begin(&mut self, viewport_size: &Vec2<i32>) -> Result<(), String> {
+        let outdated = match self.targets.first() {
+            Some(target) => {
+                let size = target.get_size();
+
+                size.x != viewport_size.x || size.y != viewport_size.y
+            }
+            None => true,
+        };
+
+        if outdated {
+            self.targets.clear();
+
+            // Deux cibles utilisées en alternance entre chaque passe.
+            for index in 0..2 {
+                let target = RenderTarget::build(viewport_size.clone(), &Vec2::default(), false)?;
+
+                target.set_label(&format!("punk_post_process_{index}"));
+
+                target.borrow_texture().bind(0)?;
+                target.borrow_texture().set_filter(TextureFilter::Linear)?;
+
+                self.targets.push(target);
+            }
+        }
+
+        if self.vao.is_none() {
+            self.vao = Some(Self::build_quad()?);
+        }
+
+        self.targets[0].bind()
+    }
+
+    // Applique toutes les passes actives, la dernière dessine dans la fenêtre.
+    pub fn apply(
+        &self,
+        state: &GlStateCache,
+        viewport_size: &Vec2<i32>,
+        source_size: &Vec2<i32>,
+    ) -> Result<(), String> {
+        let (vao, _) = self
+            .vao
+            .as_ref()
+            .ok_or("post process chain has not begun".to_string())?;
+
+        let passes: Vec<&PostProcessPass> =
+            self.passes.iter().filter(|pass| pass.enabled).collect();
+
+        let time = self.start.elapsed().as_secs_f32();
+
+        state.set_blend_mode(BlendMode::Opaque)?;
+        state.bind_vertex_array(vao.get_id())?;
+
+        for (index, pass) in passes.iter().enumerate() {
+            let source = &self.targets[index % 2];
+
+            if index + 1 == passes.len() {
+                gl_exec!(|| gl::BindFramebuffer(gl::FRAMEBUFFER, 0))?;
+                gl_exec!(|| gl::Viewport(
+                    0,
+                    0,
+                    viewport_size.x as GLsizei,
+                    viewport_size.y as GLsizei
+                ))?;
+            } else {
+                self.targets[(index + 1) % 2].bind()?;
+            }
+
+            pass.send_uniforms(state, source, viewport_size, source_size, time)?;
+
+            gl_exec!(|| gl::DrawArrays(gl::TRIANGLES, 0, 6))?;
+            stats::record_draw(6);
+        }
+
+        Ok(())
+    }
+
+    fn build_quad() -> Result<(VAO, VBO), String> {
+        #[rustfmt::skip]
+        let vertices = vec![
+            -1.0_f32, -1.0_f32, 0.0_f32, 0.0_f32,
+            1.0_f32, -1.0_f32, 1.0_f32, 0.0_f32,
+            -1.0_f32, 1.0_f32, 0.0_f32, 1.0_f32,
+            1.0_f32, -1.0_f32, 1.0_f32, 0.0_f32,
+            1.0_f32, 1.0_f32, 1.0_f32, 1.0_f32,
+            -1.0_f32, 1.0_f32, 0.0_f32, 1.0_f32,
+        ];
+
+        let vao = VAO::build()?;
+
+        vao.bind()?;
+
+        let size = (vertices.len() * mem::size_of::<f32>()) as isize;
+        let vbo = VBO::build(vertices, size, VBOType::StaticDraw)?;
+
+        vao.attrib_pointer(0, 4, (4 * mem::size_of::<f32>()) as i32, 0)?;
+        vao.enable_attrib(0)?;
+
+        VAO::unbind()?;
+
+        vao.set_label("punk_post_process_quad vao");
+        vbo.set_label("punk_post_process_quad vbo");
+
+        Ok((vao, vbo))
+    }
+}
+
+// Génère une LUT qui ne modifie pas les couleurs, point de départ pour en créer une autre.
+pub fn build_identity_lut(lut_size: i32) -> Result<Texture, String> {
+    let max = (lut_size - 1) as f32;
+    let mut pixels = Vec::with_capacity((lut_size * lut_size * lut_size * 4) as usize);
+
+    for green in 0..lut_size {
+        for blue in 0..lut_size {
+            for red in 0..lut_size {
+                pixels.push((red as f32 / max * 255.0_f32).round() as u8);
+                pixels.push((green as f32 / max * 255.0_f32).round() as u8);
+                pixels.push((blue as f32 / max * 255.0_f32).round() as u8);
+                pixels.push(255);
+            }
+        }
+    }
+
+    Texture::build(
+        Vec2 {
+            x: lut_size * lut_size,
+            y: lut_size,
+        },
+        Some(&pixels),
+        TextureFilter::Linear,
+    )
+}
@@ -0,0 +1,364 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use super::capture::Image;
+
+// Mémoire occupée par défaut par les frames d'un GIF en attente d'encodage.
+const DEFAULT_MAX_GIF_BYTES: usize = 256 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RecordSource {
+    // Contenu de la fenêtre, après post-traitement.
+    Framebuffer,
+    // Grille de pixels logique, à sa résolution native.
+    Grid,
+}
+
+#[derive(Clone)]
+pub enum RecordFormat {
+    // Les frames sont gardées en mémoire puis encodées à l'arrêt de l'enregistrement. Une frame
+    // GIF dure au moins 2 centièmes de seconde : une capture à plus de 50 images par seconde est
+    // rejouée plus lentement.
+    Gif(PathBuf),
+    // Flux vidéo brut YUV 4:4:4, lisible par ffmpeg.
+    Y4m(PathBuf),
+    // Une image PPM par frame dans le dossier donné.
+    PpmSequence(PathBuf),
+}
+
+pub struct Recorder {
+    source: RecordSource,
+    format: RecordFormat,
+    fps: u32,
+    max_gif_bytes: usize,
+    max_duration: Option<Duration>,
+    recording: bool,
+    // Début de la pause en cours.
+    paused_at: Option<Instant>,
+    // Chaque frame garde le temps pendant lequel elle reste affichée, connu à la frame suivante.
+    frames: VecDeque<(Image, Duration)>,
+    frames_bytes: usize,
+    writer: Option<BufWriter<File>>,
+    frame_size: Option<(u32, u32)>,
+    frame_count: usize,
+    started: Instant,
+    last_frame: Instant,
+}
+
+impl Recorder {
+    pub fn new(source: RecordSource, format: RecordFormat) -> Self {
+        Self {
+            source,
+            format,
+            fps: 60,
+            max_gif_bytes: DEFAULT_MAX_GIF_BYTES,
+            max_duration: None,
+            recording: false,
+            paused_at: None,
+            frames: VecDeque::new(),
+            frames_bytes: 0,
+            writer: None,
+            frame_size: None,
+            frame_count: 0,
+            started: Instant::now(),
+            last_frame: Instant::now(),
+        }
+    }
+
+    // Nombre d'images par seconde annoncé dans le flux Y4M.
+    pub fn set_fps(&mut self, fps: u32) {
+        self.fps = fps.max(1);
+    }
+
+    // Mémoire maximum en octets occupée par les frames d'un GIF, les plus anciennes sont
+    // abandonnées au-delà. La dernière frame est toujours gardée.
+    pub fn set_max_gif_bytes(&mut self, max_gif_bytes: usize) {
+        self.max_gif_bytes = max_gif_bytes;
+    }
+
+    pub fn get_max_gif_bytes(&self) -> usize {
+        self.max_gif_bytes
+    }
+
+    // L'enregistrement s'arrête tout seul une fois cette durée écoulée, pauses non comprises.
+    pub fn set_max_duration(&mut self, max_duration: Option<Duration>) {
+        self.max_duration = max_duration;
+    }
+
+    pub fn get_source(&self) -> RecordSource {
+        self.source
+    }
+
+    // Vrai si les frames sont capturées, c'est-à-dire ni arrêté ni en pause.
+    pub fn is_recording(&self) -> bool {
+        self.recording && self.paused_at.is_none()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    // Commence un nouvel enregistrement, les fichiers précédents sont remplacés. Reprend
+    // l'enregistrement s'il est en pause.
+    pub fn start(&mut self) -> Result<(), String> {
+        if self.recording {
+            self.resume();
+
+            return Ok(());
+        }
+
+        self.frames.clear();
+        self.frames_bytes = 0;
+        self.frame_size = None;
+        self.frame_count = 0;
+
+        match &self.format {
+            RecordFormat::Y4m(path) => {
+                let file = File::create(path).map_err(|e| e.to_string())?;
+
+                self.writer = Some(BufWriter::new(file));
+            }
+            RecordFormat::PpmSequence(directory) => {
+                fs::create_dir_all(directory).map_err(|e| e.to_string())?;
+            }
+            RecordFormat::Gif(_) => (),
+        }
+
+        self.started = Instant::now();
+        self.last_frame = self.started;
+        self.paused_at = None;
+        self.recording = true;
+
+        Ok(())
+    }
+
+    // Le fichier reste ouvert et les frames déjà capturées sont gardées.
+    pub fn pause(&mut self) {
+        if !self.is_recording() {
+            return;
+        }
+
+        let now = Instant::now();
+
+        self.hold_last_frame(now);
+        self.paused_at = Some(now);
+    }
+
+    // La durée de la pause n'est comptée ni dans le délai des frames ni dans la durée maximum.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            let now = Instant::now();
+
+            self.started += now - paused_at;
+            self.last_frame = now;
+        }
+    }
+
+    // Termine l'enregistrement et écrit ce qui reste en mémoire.
+    pub fn stop(&mut self) -> Result<(), String> {
+        if !self.recording {
+            return Ok(());
+        }
+
+        // La dernière frame reste affichée jusqu'à l'arrêt.
+        if self.paused_at.is_none() {
+            self.hold_last_frame(Instant::now());
+        }
+
+        self.recording = false;
+        self.paused_at = None;
+
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush().map_err(|e| e.to_string())?;
+        }
+
+        if let RecordFormat::Gif(path) = &self.format {
+            let path = path.clone();
+
+            self.encode_gif(&path)?;
+        }
+
+        Ok(())
+    }
+
+    // Met en pause ou reprend l'enregistrement, le démarre s'il est arrêté.
+    pub fn toggle(&mut self) -> Result<(), String> {
+        if self.is_recording() {
+            self.pause();
+
+            Ok(())
+        } else {
+            self.start()
+        }
+    }
+
+    pub fn capture_frame(&mut self, image: Image) -> Result<(), String> {
+        if !self.is_recording() {
+            return Ok(());
+        }
+
+        if let Some(max_duration) = self.max_duration {
+            if self.started.elapsed() >= max_duration {
+                return self.stop();
+            }
+        }
+
+        // Toutes les frames d'un enregistrement doivent avoir la même taille.
+        match self.frame_size {
+            Some(size) if size != (image.width, image.height) => {
+                self.stop()?;
+
+                return Err("frame size changed during recording".to_string());
+            }
+            Some(_) => (),
+            None => self.frame_size = Some((image.width, image.height)),
+        }
+
+        self.hold_last_frame(Instant::now());
+
+        match &self.format {
+            RecordFormat::Gif(_) => {
+                let bytes = image.pixels.len();
+
+                while !self.frames.is_empty() && self.frames_bytes + bytes > self.max_gif_bytes {
+                    if let Some((old, _)) = self.frames.pop_front() {
+                        self.frames_bytes -= old.pixels.len();
+                    }
+                }
+
+                self.frames_bytes += bytes;
+                self.frames.push_back((image, Duration::ZERO));
+            }
+            RecordFormat::Y4m(_) => self.write_y4m_frame(&image)?,
+            RecordFormat::PpmSequence(directory) => {
+                let path = directory.join(format!("frame_{:06}.ppm", self.frame_count));
+
+                Self::write_ppm(&image, path)?;
+            }
+        }
+
+        self.frame_count += 1;
+
+        Ok(())
+    }
+
+    // Ajoute le temps écoulé depuis la dernière capture au délai de la frame précédente.
+    fn hold_last_frame(&mut self, now: Instant) {
+        if let Some((_, delay)) = self.frames.back_mut() {
+            *delay += now - self.last_frame;
+        }
+
+        self.last_frame = now;
+    }
+
+    fn write_y4m_frame(&mut self, image: &Image) -> Result<(), String> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or("y4m stream is not open".to_string())?;
+
+        if self.frame_count == 0 {
+            writeln!(
+                writer,
+                "YUV4MPEG2 W{} H{} F{}:1 Ip A1:1 C444",
+                image.width, image.height, self.fps
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        let pixel_count = (image.width * image.height) as usize;
+        let mut planes = vec![0_u8; pixel_count * 3];
+
+        // Conversion RGB vers YCbCr (BT.601, plage limitée).
+        for (i, rgba) in image.pixels.chunks_exact(4).enumerate() {
+            let r = rgba[0] as f32 / 255.0_f32;
+            let g = rgba[1] as f32 / 255.0_f32;
+            let b = rgba[2] as f32 / 255.0_f32;
+
+            let y = 16.0_f32 + 65.481_f32 * r + 128.553_f32 * g + 24.966_f32 * b;
+            let u = 128.0_f32 - 37.797_f32 * r - 74.203_f32 * g + 112.0_f32 * b;
+            let v = 128.0_f32 + 112.0_f32 * r - 93.786_f32 * g - 18.214_f32 * b;
+
+            planes[i] = y.round() as u8;
+            planes[pixel_count + i] = u.round() as u8;
+            planes[pixel_count * 2 + i] = v.round() as u8;
+        }
+
+        writer.write_all(b"FRAME\n").map_err(|e| e.to_string())?;
+        writer.write_all(&planes).map_err(|e| e.to_string())
+    }
+
+    fn write_ppm(image: &Image, path: PathBuf) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| e.to_string())?;
+        let mut writer = BufWriter::new(file);
+
+        write!(writer, "P6\n{} {}\n255\n", image.width, image.height).map_err(|e| e.to_string())?;
+
+        let rgb: Vec<u8> = image
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+            .collect();
+
+        writer.write_all(&rgb).map_err(|e| e.to_string())?;
+        writer.flush().map_err(|e| e.to_string())
+    }
+
+    fn encode_gif(&mut self, path: &PathBuf) -> Result<(), String> {
+        let (width, height) = match self.frame_size {
+            Some(size) => size,
+            None => return Ok(()),
+        };
+
+        if width > u16::MAX as u32 || height > u16::MAX as u32 {
+            return Err("frame is too large for a gif".to_string());
+        }
+
+        let file = File::create(path).map_err(|e| e.to_string())?;
+
+        let mut encoder = gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+            .map_err(|e| e.to_string())?;
+
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| e.to_string())?;
+
+        self.frames_bytes = 0;
+
+        // Reste de l'arrondi des délais précédents, en microsecondes, reporté sur la frame
+        // suivante afin que la durée totale ne dérive pas.
+        let mut remainder = 0_i64;
+
+        for (mut image, delay) in self.frames.drain(..) {
+            let mut frame =
+                gif::Frame::from_rgba_speed(width as u16, height as u16, &mut image.pixels, 10);
+
+            // Le délai d'un GIF s'exprime en centièmes de seconde.
+            let target = delay.as_micros() as i64 + remainder;
+            let rounded = (target + 5_000) / 10_000;
+            let centiseconds = rounded.clamp(2, u16::MAX as i64);
+
+            // Le temps ajouté par le minimum de 2 centièmes n'est pas rattrapé.
+            remainder = if rounded < 2 {
+                0
+            } else {
+                target - centiseconds * 10_000
+            };
+
+            frame.delay = centiseconds as u16;
+
+            encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+        }
+
+        Ok(())
+    }
+}
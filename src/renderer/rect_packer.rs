@@ -0,0 +1,119 @@
+// Range des rectangles par étagères : chaque ligne a la hauteur de son premier rectangle et se
+// remplit de gauche à droite. Simple et efficace pour des tailles proches comme des glyphes.
+#[derive(Clone, Debug)]
+pub struct RectPacker {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    // Début de la prochaine étagère.
+    next_y: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    // Début de la place libre sur l'étagère.
+    x: u32,
+}
+
+impl RectPacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            shelves: Vec::new(),
+            next_y: 0,
+        }
+    }
+
+    // Retourne le coin haut gauche de la place réservée, ou `None` si le rectangle ne rentre
+    // plus.
+    pub fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        if width > self.width || height > self.height {
+            return None;
+        }
+
+        // L'étagère la moins haute qui convient, afin de limiter la place perdue au-dessus.
+        let best = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| height <= shelf.height && shelf.x + width <= self.width)
+            .min_by_key(|shelf| shelf.height);
+
+        if let Some(shelf) = best {
+            let position = (shelf.x, shelf.y);
+
+            shelf.x += width;
+
+            return Some(position);
+        }
+
+        if self.next_y + height > self.height {
+            return None;
+        }
+
+        let position = (0, self.next_y);
+
+        self.shelves.push(Shelf {
+            y: self.next_y,
+            height,
+            x: width,
+        });
+        self.next_y += height;
+
+        Some(position)
+    }
+
+    // Libère toute la place.
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+        self.next_y = 0;
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_shelves() {
+        let mut packer = RectPacker::new(10, 10);
+
+        assert_eq!(packer.insert(4, 3), Some((0, 0)));
+        assert_eq!(packer.insert(4, 2), Some((4, 0)));
+        // Plus assez de place sur la première étagère.
+        assert_eq!(packer.insert(4, 3), Some((0, 3)));
+    }
+
+    #[test]
+    fn test_best_fit_shelf() {
+        let mut packer = RectPacker::new(10, 10);
+
+        assert_eq!(packer.insert(2, 3), Some((0, 0)));
+        assert_eq!(packer.insert(2, 5), Some((0, 3)));
+
+        // Les deux étagères conviennent, la moins haute est choisie.
+        assert_eq!(packer.insert(2, 2), Some((2, 0)));
+        assert_eq!(packer.insert(2, 4), Some((2, 3)));
+    }
+
+    #[test]
+    fn test_overflow_and_clear() {
+        let mut packer = RectPacker::new(8, 8);
+
+        assert_eq!(packer.insert(9, 1), None);
+        assert_eq!(packer.insert(1, 9), None);
+
+        assert_eq!(packer.insert(8, 6), Some((0, 0)));
+        assert_eq!(packer.insert(8, 3), None);
+
+        packer.clear();
+
+        assert_eq!(packer.insert(8, 8), Some((0, 0)));
+    }
+}
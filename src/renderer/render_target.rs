@@ -0,0 +1,217 @@
+use gl::types::GLsizei;
+
+use crate::{
+    gl_exec,
+    maths::vec::{Vec2, Vec4},
+};
+
+use super::{
+    camera::Camera2D,
+    capture::Image,
+    draw::Draw,
+    gl_object::{GlObject, GlObjectKind},
+    texture::{Texture, TextureFilter},
+    Renderer,
+};
+
+pub struct RenderTarget {
+    fbo: GlObject,
+    texture: Texture,
+    depth_stencil: Option<GlObject>,
+    clear_color: Vec4<f32>,
+    pub(crate) camera: Camera2D,
+    half_extents: Vec2<f32>,
+    pub(crate) drawing_indexes: Vec<usize>,
+}
+
+impl RenderTarget {
+    pub fn build(
+        size: Vec2<i32>,
+        display_size: &Vec2<i32>,
+        depth_stencil: bool,
+    ) -> Result<Self, String> {
+        if size.x <= 0 || size.y <= 0 {
+            return Err("render target size must be positive".to_string());
+        }
+
+        let mut fbo = 0;
+
+        gl_exec!(|| gl::GenFramebuffers(1, &mut fbo))?;
+
+        let fbo = GlObject::new(GlObjectKind::Framebuffer, fbo);
+
+        gl_exec!(|| gl::BindFramebuffer(gl::FRAMEBUFFER, fbo.get_id()))?;
+
+        let texture = Texture::build(size.clone(), None, TextureFilter::Nearest)?;
+
+        // Attache la texture en tant que tampon de couleur.
+        gl_exec!(|| gl::FramebufferTexture2D(
+            gl::FRAMEBUFFER,
+            gl::COLOR_ATTACHMENT0,
+            gl::TEXTURE_2D,
+            texture.get_id(),
+            0
+        ))?;
+
+        let depth_stencil = if depth_stencil {
+            let mut rbo = 0;
+
+            gl_exec!(|| gl::GenRenderbuffers(1, &mut rbo))?;
+
+            let rbo = GlObject::new(GlObjectKind::Renderbuffer, rbo);
+
+            gl_exec!(|| gl::BindRenderbuffer(gl::RENDERBUFFER, rbo.get_id()))?;
+            gl_exec!(|| gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH24_STENCIL8,
+                size.x as GLsizei,
+                size.y as GLsizei
+            ))?;
+            gl_exec!(|| gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                rbo.get_id()
+            ))?;
+            gl_exec!(|| gl::BindRenderbuffer(gl::RENDERBUFFER, 0))?;
+
+            Some(rbo)
+        } else {
+            None
+        };
+
+        let mut status = 0;
+
+        gl_exec!(|| status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER))?;
+        gl_exec!(|| gl::BindFramebuffer(gl::FRAMEBUFFER, 0))?;
+
+        let render_target = Self {
+            fbo,
+            texture,
+            depth_stencil,
+            clear_color: Vec4 {
+                x: 0.0_f32,
+                y: 0.0_f32,
+                z: 0.0_f32,
+                w: 0.0_f32,
+            },
+            camera: Camera2D::centered_on_display(display_size),
+            half_extents: Vec2 {
+                x: display_size.x as f32 * 0.5_f32,
+                y: display_size.y as f32 * 0.5_f32,
+            },
+            drawing_indexes: Vec::new(),
+        };
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(format!("framebuffer is not complete (status {status:#x})"));
+        }
+
+        Ok(render_target)
+    }
+
+    pub fn bind(&self) -> Result<(), String> {
+        let size = self.texture.get_size();
+
+        gl_exec!(|| gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo.get_id()))?;
+        gl_exec!(|| gl::Viewport(0, 0, size.x as GLsizei, size.y as GLsizei))
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        let mut mask = gl::COLOR_BUFFER_BIT;
+
+        if self.depth_stencil.is_some() {
+            mask |= gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT;
+        }
+
+        gl_exec!(|| gl::ClearColor(
+            self.clear_color.x,
+            self.clear_color.y,
+            self.clear_color.z,
+            self.clear_color.w
+        ))?;
+        gl_exec!(|| gl::Clear(mask))
+    }
+
+    // Dessine les objets redirigés vers cette cible puis revient sur le framebuffer par défaut.
+    pub fn render(
+        &self,
+        renderer: &Renderer,
+        drawing_objects: &[Box<dyn Draw>],
+    ) -> Result<(), String> {
+        self.bind()?;
+        self.clear()?;
+
+        let projection = self.camera.view_projection(&self.half_extents);
+
+        for index in self.drawing_indexes.iter() {
+            let drawing_object = drawing_objects
+                .get(*index)
+                .ok_or(format!("no drawing object at index {index}"))?;
+
+            if drawing_object.is_visible() {
+                renderer.set_blend_mode(drawing_object.get_blend_mode())?;
+                drawing_object.draw(renderer, &projection)?;
+            }
+        }
+
+        renderer.bind_default_framebuffer()
+    }
+
+    pub fn capture(&self, renderer: &Renderer) -> Result<Image, String> {
+        self.bind()?;
+
+        let image = Image::read_framebuffer(&self.get_size());
+
+        renderer.bind_default_framebuffer()?;
+
+        image
+    }
+
+    pub fn set_clear_color(&mut self, color: Vec4<f32>) {
+        self.clear_color = color;
+    }
+
+    pub fn borrow_camera(&self) -> &Camera2D {
+        &self.camera
+    }
+
+    pub fn set_camera(&mut self, camera: Camera2D) {
+        self.camera = camera;
+    }
+
+    pub fn world_to_screen(&self, world: &Vec2<f32>) -> Vec2<f32> {
+        self.camera
+            .world_to_screen(world, &self.half_extents, &self.get_size())
+    }
+
+    pub fn screen_to_world(&self, screen: &Vec2<f32>) -> Vec2<f32> {
+        self.camera
+            .screen_to_world(screen, &self.half_extents, &self.get_size())
+    }
+
+    pub fn set_drawing_indexes(&mut self, indexes: Vec<usize>) {
+        self.drawing_indexes = indexes;
+    }
+
+    pub fn borrow_drawing_indexes(&self) -> &Vec<usize> {
+        &self.drawing_indexes
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.fbo.set_label(label);
+        self.texture.set_label(&format!("{label} color"));
+
+        if let Some(rbo) = &self.depth_stencil {
+            rbo.set_label(&format!("{label} depth stencil"));
+        }
+    }
+
+    pub fn borrow_texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    pub fn get_size(&self) -> Vec2<i32> {
+        self.texture.get_size()
+    }
+}
@@ -0,0 +1,95 @@
+// Champ de distance signée d'un bitmap de couverture, agrandi de `spread` pixels de chaque côté.
+// 128 correspond au bord du glyphe, les valeurs plus grandes sont à l'intérieur et la distance
+// est saturée à `spread` pixels.
+// Retourne les pixels, une ligne de `width + 2 * spread` octets par rangée.
+pub fn generate_sdf(width: u32, height: u32, pitch: u32, coverage: &[u8], spread: u32) -> Vec<u8> {
+    let spread = spread.max(1) as i64;
+    let (width, height, pitch) = (width as i64, height as i64, pitch as i64);
+
+    let inside = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && x < width
+            && y < height
+            && coverage
+                .get((y * pitch + x) as usize)
+                .is_some_and(|value| *value >= 128)
+    };
+
+    let out_width = width + spread * 2;
+    let out_height = height + spread * 2;
+
+    let mut pixels = Vec::with_capacity((out_width * out_height) as usize);
+
+    for out_y in 0..out_height {
+        for out_x in 0..out_width {
+            let (x, y) = (out_x - spread, out_y - spread);
+            let state = inside(x, y);
+
+            // Pixel le plus proche de l'autre côté du bord, cherché dans la zone d'étalement.
+            let mut nearest = ((spread + 1) * (spread + 1)) as f32;
+
+            for dy in -spread..=spread {
+                for dx in -spread..=spread {
+                    if inside(x + dx, y + dy) != state {
+                        nearest = nearest.min((dx * dx + dy * dy) as f32);
+                    }
+                }
+            }
+
+            // Le bord passe entre les centres de deux pixels voisins.
+            let distance = nearest.sqrt() - 0.5_f32;
+            let signed = if state { distance } else { -distance };
+
+            let value = 0.5_f32 + signed / (spread * 2) as f32;
+
+            pixels.push((value.clamp(0.0_f32, 1.0_f32) * 255.0_f32).round() as u8);
+        }
+    }
+
+    pixels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Carré plein de 4×4 pixels.
+    fn square() -> Vec<u8> {
+        vec![255; 16]
+    }
+
+    #[test]
+    fn test_output_size() {
+        let field = generate_sdf(4, 3, 5, &[0; 15], 2);
+
+        assert_eq!(field.len(), (4 + 2 * 2) * (3 + 2 * 2));
+    }
+
+    #[test]
+    fn test_inside_and_outside() {
+        let spread = 3;
+        let field = generate_sdf(4, 4, 4, &square(), spread);
+        let width = 4 + spread * 2;
+
+        let at = |x: u32, y: u32| field[(y * width + x) as usize];
+
+        // Centre du carré, puis coin de l'image loin de tout pixel.
+        assert!(at(spread + 2, spread + 2) > 128);
+        assert!(at(0, 0) < 128);
+    }
+
+    #[test]
+    fn test_edge_is_centered() {
+        let spread = 3;
+        let field = generate_sdf(4, 4, 4, &square(), spread);
+        let width = 4 + spread * 2;
+
+        // Les deux pixels de part et d'autre du bord gauche.
+        let inside = field[((spread + 1) * width + spread) as usize] as i32;
+        let outside = field[((spread + 1) * width + spread - 1) as usize] as i32;
+
+        assert!(inside > 128 && outside < 128);
+        assert!((inside + outside - 256).abs() <= 1);
+    }
+}
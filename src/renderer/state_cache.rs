@@ -0,0 +1,229 @@
+use std::cell::Cell;
+
+use gl::types::GLuint;
+
+use crate::gl_exec;
+
+use super::{blend::BlendMode, stats};
+
+// Nombre d'unités de texture suivies, les suivantes sont toujours liées.
+const TRACKED_TEXTURE_UNITS: usize = 16;
+
+// État lié directement par les objets OpenGL (VAO, VBO, textures...) sans passer par le cache.
+#[derive(Clone, Copy)]
+pub enum DirectBinding {
+    Program,
+    VertexArray,
+    ArrayBuffer,
+    // Une texture liée sur une unité quelconque, l'unité active a pu changer.
+    Texture,
+}
+
+thread_local! {
+    // Liaisons directes depuis le dernier appel au cache, un bit par `DirectBinding`. Comme les
+    // compteurs de `stats`, elles sont tenues par thread.
+    static DIRECT_BINDINGS: Cell<u8> = const { Cell::new(0) };
+}
+
+// À appeler après chaque liaison faite sans le cache, qui oublie l'état correspondant avant son
+// prochain appel.
+pub fn record_direct_binding(binding: DirectBinding) {
+    DIRECT_BINDINGS.with(|bindings| bindings.set(bindings.get() | 1 << binding as u8));
+}
+
+// Mémorise l'état lié dans le contexte OpenGL de la fenêtre afin de ne pas répéter les appels qui
+// ne changeraient rien. `None` signifie que l'état est inconnu : le prochain appel est toujours
+// transmis.
+pub struct GlStateCache {
+    program: Cell<Option<GLuint>>,
+    vertex_array: Cell<Option<GLuint>>,
+    array_buffer: Cell<Option<GLuint>>,
+    active_texture_unit: Cell<Option<u32>>,
+    textures: [Cell<Option<GLuint>>; TRACKED_TEXTURE_UNITS],
+    blend_mode: Cell<Option<BlendMode>>,
+}
+
+impl Default for GlStateCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GlStateCache {
+    pub fn new() -> Self {
+        Self {
+            program: Cell::new(None),
+            vertex_array: Cell::new(None),
+            array_buffer: Cell::new(None),
+            active_texture_unit: Cell::new(None),
+            textures: Default::default(),
+            blend_mode: Cell::new(None),
+        }
+    }
+
+    // Oublie l'état lié directement depuis le dernier appel.
+    fn forget_direct_bindings(&self) {
+        let bindings = DIRECT_BINDINGS.with(|bindings| bindings.replace(0));
+
+        if bindings == 0 {
+            return;
+        }
+
+        let recorded = |binding: DirectBinding| bindings & (1 << binding as u8) != 0;
+
+        if recorded(DirectBinding::Program) {
+            self.program.set(None);
+        }
+
+        if recorded(DirectBinding::VertexArray) {
+            self.vertex_array.set(None);
+        }
+
+        if recorded(DirectBinding::ArrayBuffer) {
+            self.array_buffer.set(None);
+        }
+
+        if recorded(DirectBinding::Texture) {
+            self.active_texture_unit.set(None);
+            self.invalidate_textures();
+        }
+    }
+
+    // Retourne vrai si `value` est déjà l'état courant, sinon le mémorise.
+    fn unchanged<T: Copy + PartialEq>(&self, cell: &Cell<Option<T>>, value: T) -> bool {
+        self.forget_direct_bindings();
+
+        if cell.get() == Some(value) {
+            stats::record_skipped_state_change();
+
+            return true;
+        }
+
+        cell.set(Some(value));
+        stats::record_state_change();
+
+        false
+    }
+
+    pub fn use_program(&self, id: GLuint) -> Result<(), String> {
+        if self.unchanged(&self.program, id) {
+            return Ok(());
+        }
+
+        gl_exec!(|| gl::UseProgram(id))
+    }
+
+    pub fn bind_vertex_array(&self, id: GLuint) -> Result<(), String> {
+        if self.unchanged(&self.vertex_array, id) {
+            return Ok(());
+        }
+
+        gl_exec!(|| gl::BindVertexArray(id))
+    }
+
+    pub fn bind_array_buffer(&self, id: GLuint) -> Result<(), String> {
+        if self.unchanged(&self.array_buffer, id) {
+            return Ok(());
+        }
+
+        gl_exec!(|| gl::BindBuffer(gl::ARRAY_BUFFER, id))
+    }
+
+    pub fn active_texture(&self, unit: u32) -> Result<(), String> {
+        if self.unchanged(&self.active_texture_unit, unit) {
+            return Ok(());
+        }
+
+        gl_exec!(|| gl::ActiveTexture(gl::TEXTURE0 + unit))
+    }
+
+    // Lie une texture 2D sur l'unité `unit`, qui devient l'unité active si la liaison change.
+    pub fn bind_texture(&self, unit: u32, id: GLuint) -> Result<(), String> {
+        self.forget_direct_bindings();
+
+        match self.textures.get(unit as usize) {
+            Some(texture) if texture.get() == Some(id) => {
+                stats::record_skipped_state_change();
+
+                Ok(())
+            }
+            texture => {
+                self.active_texture(unit)?;
+
+                gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, id))?;
+                stats::record_state_change();
+
+                if let Some(texture) = texture {
+                    texture.set(Some(id));
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    pub fn set_blend_mode(&self, blend_mode: BlendMode) -> Result<(), String> {
+        if self.unchanged(&self.blend_mode, blend_mode) {
+            return Ok(());
+        }
+
+        blend_mode.apply()
+    }
+
+    pub fn get_blend_mode(&self) -> Option<BlendMode> {
+        self.blend_mode.get()
+    }
+
+    // À appeler lorsque l'état a été modifié sans passer par le cache, par exemple lors de la
+    // création d'objets OpenGL.
+    pub fn invalidate(&self) {
+        DIRECT_BINDINGS.with(|bindings| bindings.set(0));
+
+        self.program.set(None);
+        self.vertex_array.set(None);
+        self.array_buffer.set(None);
+        self.active_texture_unit.set(None);
+        self.invalidate_textures();
+        self.blend_mode.set(None);
+    }
+
+    // Les textures ont été liées ou supprimées directement.
+    pub fn invalidate_textures(&self) {
+        for texture in self.textures.iter() {
+            texture.set(None);
+        }
+    }
+
+    pub fn invalidate_blend_mode(&self) {
+        self.blend_mode.set(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forget_direct_bindings() {
+        let state = GlStateCache::new();
+
+        state.vertex_array.set(Some(3));
+        state.array_buffer.set(Some(5));
+        state.textures[2].set(Some(7));
+
+        record_direct_binding(DirectBinding::VertexArray);
+        record_direct_binding(DirectBinding::Texture);
+
+        state.forget_direct_bindings();
+
+        assert_eq!(state.vertex_array.get(), None);
+        assert_eq!(state.array_buffer.get(), Some(5));
+        assert_eq!(state.textures[2].get(), None);
+
+        // Les liaisons ne sont oubliées qu'une fois.
+        state.vertex_array.set(Some(3));
+        state.forget_direct_bindings();
+
+        assert_eq!(state.vertex_array.get(), Some(3));
+    }
+}
@@ -0,0 +1,65 @@
+use std::cell::Cell;
+
+// Compteurs d'une frame. Ils sont tenus par thread, chaque fenêtre faisant son rendu dans la
+// boucle qui l'a lancée.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RenderStats {
+    pub draw_calls: u64,
+    pub vertices: u64,
+    // Changements d'état réellement transmis à OpenGL par le cache.
+    pub state_changes: u64,
+    // Changements évités car l'état était déjà en place.
+    pub skipped_state_changes: u64,
+    pub texture_uploads: u64,
+}
+
+thread_local! {
+    static CURRENT_FRAME: Cell<RenderStats> = const {
+        Cell::new(RenderStats {
+            draw_calls: 0,
+            vertices: 0,
+            state_changes: 0,
+            skipped_state_changes: 0,
+            texture_uploads: 0,
+        })
+    };
+}
+
+fn update(f: impl FnOnce(&mut RenderStats)) {
+    CURRENT_FRAME.with(|current| {
+        let mut stats = current.get();
+
+        f(&mut stats);
+
+        current.set(stats);
+    });
+}
+
+pub fn record_draw(vertices: usize) {
+    update(|stats| {
+        stats.draw_calls += 1;
+        stats.vertices += vertices as u64;
+    });
+}
+
+pub fn record_state_change() {
+    update(|stats| stats.state_changes += 1);
+}
+
+pub fn record_skipped_state_change() {
+    update(|stats| stats.skipped_state_changes += 1);
+}
+
+pub fn record_texture_upload() {
+    update(|stats| stats.texture_uploads += 1);
+}
+
+// Compteurs depuis le dernier appel à `take_frame_stats`.
+pub fn current_frame_stats() -> RenderStats {
+    CURRENT_FRAME.with(|current| current.get())
+}
+
+// Retourne les compteurs de la frame écoulée et les remet à zéro.
+pub fn take_frame_stats() -> RenderStats {
+    CURRENT_FRAME.with(|current| current.replace(RenderStats::default()))
+}
@@ -0,0 +1,315 @@
+use crate::maths::vec::Vec2;
+
+use super::{
+    font::{Charactere, FontContext},
+    state_cache::GlStateCache,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLayoutOptions {
+    // Largeur maximale d'une ligne en pixels de la police, `None` pour ne couper qu'aux `\n`.
+    pub max_width: Option<f32>,
+    pub align: TextAlign,
+    // Multiplie la hauteur de ligne de la police.
+    pub line_spacing: f32,
+}
+
+impl Default for TextLayoutOptions {
+    fn default() -> Self {
+        Self {
+            max_width: None,
+            align: TextAlign::Left,
+            line_spacing: 1.0_f32,
+        }
+    }
+}
+
+// Boîte occupée par un texte, relative à l'origine du texte (la ligne de base de la première
+// ligne, y vers le bas).
+#[derive(Clone, Default)]
+pub struct TextBounds {
+    pub min: Vec2<f32>,
+    pub max: Vec2<f32>,
+}
+
+impl TextBounds {
+    pub fn get_width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn get_height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn get_center(&self) -> Vec2<f32> {
+        Vec2 {
+            x: (self.min.x + self.max.x) / 2.0_f32,
+            y: (self.min.y + self.max.y) / 2.0_f32,
+        }
+    }
+
+    pub fn scaled(&self, x: f32, y: f32) -> Self {
+        Self {
+            min: Vec2 {
+                x: self.min.x * x,
+                y: self.min.y * y,
+            },
+            max: Vec2 {
+                x: self.max.x * x,
+                y: self.max.y * y,
+            },
+        }
+    }
+}
+
+// Glyphe placé par la mise en page, `position` est le point de départ sur la ligne de base.
+pub struct PositionedGlyph {
+    // Position du caractère dans le texte, en caractères.
+    pub index: usize,
+    pub c: char,
+    pub glyph: Charactere,
+    pub position: Vec2<f32>,
+}
+
+pub struct TextLayout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub bounds: TextBounds,
+    pub line_count: usize,
+}
+
+struct LineGlyph {
+    index: usize,
+    c: char,
+    // Police de la chaîne qui fournit le glyphe.
+    font: usize,
+    glyph: Charactere,
+    x: f32,
+}
+
+#[derive(Default)]
+struct Line {
+    glyphs: Vec<LineGlyph>,
+    pen: f32,
+    // Fin du dernier caractère visible, les espaces en fin de ligne ne comptent pas.
+    width: f32,
+    // Index du premier caractère après le dernier espace, où la ligne peut être coupée.
+    break_at: Option<usize>,
+}
+
+impl Line {
+    fn has_content(&self) -> bool {
+        self.glyphs.iter().any(|glyph| !glyph.c.is_whitespace())
+    }
+}
+
+struct LineBuilder<'a> {
+    font_context: &'a FontContext,
+    fonts: &'a [String],
+    size: u32,
+    max_width: Option<f32>,
+    lines: Vec<Line>,
+    line: Line,
+}
+
+impl LineBuilder<'_> {
+    fn push(
+        &mut self,
+        index: usize,
+        c: char,
+        font: usize,
+        glyph: Charactere,
+    ) -> Result<(), String> {
+        let kerning = self.kerning(c, font)?;
+        let advance = glyph.get_advance();
+
+        if c.is_whitespace() {
+            self.place(index, c, font, glyph, kerning, advance);
+            self.line.break_at = Some(self.line.glyphs.len());
+
+            return Ok(());
+        }
+
+        let overflow = match self.max_width {
+            Some(max_width) => self.line.pen + kerning + advance > max_width,
+            None => false,
+        };
+
+        if !overflow || !self.line.has_content() {
+            self.place(index, c, font, glyph, kerning, advance);
+
+            return Ok(());
+        }
+
+        // Le mot en cours passe à la ligne suivante, ou seulement le caractère s'il occupe déjà
+        // toute la ligne.
+        let word = match self.line.break_at {
+            Some(index) if index < self.line.glyphs.len() => self.line.glyphs.split_off(index),
+            _ => Vec::new(),
+        };
+
+        self.new_line();
+
+        for glyph in word {
+            self.push(glyph.index, glyph.c, glyph.font, glyph.glyph)?;
+        }
+
+        self.push(index, c, font, glyph)
+    }
+
+    fn place(
+        &mut self,
+        index: usize,
+        c: char,
+        font: usize,
+        glyph: Charactere,
+        kerning: f32,
+        advance: f32,
+    ) {
+        let x = self.line.pen + kerning;
+
+        self.line.glyphs.push(LineGlyph {
+            index,
+            c,
+            font,
+            glyph,
+            x,
+        });
+        self.line.pen = x + advance;
+
+        if !c.is_whitespace() {
+            self.line.width = self.line.pen;
+        }
+    }
+
+    // Deux glyphes de polices différentes ne sont pas rapprochés.
+    fn kerning(&self, c: char, font: usize) -> Result<f32, String> {
+        match self.line.glyphs.last() {
+            Some(previous) if previous.font == font => {
+                self.font_context
+                    .get_kerning(&self.fonts[font], self.size, previous.c, c)
+            }
+            _ => Ok(0.0_f32),
+        }
+    }
+
+    fn new_line(&mut self) {
+        let mut line = std::mem::take(&mut self.line);
+
+        // Recalcule la largeur, le mot déplacé a pu la modifier.
+        line.width = line
+            .glyphs
+            .iter()
+            .rev()
+            .find(|glyph| !glyph.c.is_whitespace())
+            .map(|glyph| glyph.x + glyph.glyph.get_advance())
+            .unwrap_or(0.0_f32);
+
+        self.lines.push(line);
+    }
+}
+
+impl TextLayout {
+    // Coupe le texte aux `\n` et, avec une largeur maximale, entre les mots. Chaque caractère
+    // est pris dans la première police de `fonts` qui le contient, les mesures verticales sont
+    // celles de la première. Les glyphes sont rastérisés si besoin.
+    pub fn build(
+        font_context: &FontContext,
+        fonts: &[String],
+        size: u32,
+        text: &str,
+        options: &TextLayoutOptions,
+        state: &GlStateCache,
+    ) -> Result<Self, String> {
+        let primary = fonts.first().ok_or("no font given".to_string())?;
+
+        let metrics = font_context.get_line_metrics(primary, size)?;
+        let line_height = metrics.line_height * options.line_spacing;
+
+        let mut builder = LineBuilder {
+            font_context,
+            fonts,
+            size,
+            max_width: options.max_width,
+            lines: Vec::new(),
+            line: Line::default(),
+        };
+
+        for (index, c) in text.chars().enumerate() {
+            match c {
+                '\n' => builder.new_line(),
+                // Un `\r` de fin de ligne Windows n'est pas affiché.
+                '\r' => continue,
+                _ => {
+                    let font = font_context.resolve_font(fonts, c)?;
+                    let glyph = font_context.get_glyph(&fonts[font], size, c, state)?;
+
+                    builder.push(index, c, font, glyph)?;
+                }
+            }
+        }
+
+        builder.new_line();
+
+        let lines = builder.lines;
+
+        let block_width = match options.max_width {
+            Some(max_width) => max_width,
+            None => lines.iter().map(|line| line.width).fold(0.0_f32, f32::max),
+        };
+
+        let mut glyphs = Vec::new();
+        let mut min_x = f32::MAX;
+        let mut max_x = f32::MIN;
+
+        for (index, line) in lines.iter().enumerate() {
+            // Les glyphes restent alignés sur les pixels de la police.
+            let offset = match options.align {
+                TextAlign::Left => 0.0_f32,
+                TextAlign::Center => ((block_width - line.width) / 2.0_f32).floor(),
+                TextAlign::Right => (block_width - line.width).floor(),
+            };
+
+            min_x = min_x.min(offset);
+            max_x = max_x.max(offset + line.width);
+
+            let baseline = index as f32 * line_height;
+
+            for glyph in line.glyphs.iter() {
+                glyphs.push(PositionedGlyph {
+                    index: glyph.index,
+                    c: glyph.c,
+                    glyph: glyph.glyph.share(),
+                    position: Vec2 {
+                        x: offset + glyph.x,
+                        y: baseline,
+                    },
+                });
+            }
+        }
+
+        let line_count = lines.len();
+
+        Ok(Self {
+            glyphs,
+            bounds: TextBounds {
+                min: Vec2 {
+                    x: min_x,
+                    y: -metrics.ascender,
+                },
+                max: Vec2 {
+                    x: max_x,
+                    y: (line_count - 1) as f32 * line_height - metrics.descender,
+                },
+            },
+            line_count,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use std::{os, ptr};
+
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
+
+use crate::{gl_exec, maths::vec::Vec2};
+
+use super::{
+    gl_object::{GlObject, GlObjectKind},
+    state_cache::{self, DirectBinding},
+    stats,
+};
+
+#[derive(Clone, Copy)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+impl TextureFilter {
+    pub fn get_gl_filter(&self) -> GLenum {
+        match self {
+            TextureFilter::Nearest => gl::NEAREST,
+            TextureFilter::Linear => gl::LINEAR,
+        }
+    }
+}
+
+pub struct Texture {
+    handle: GlObject,
+    size: Vec2<i32>,
+}
+
+impl Texture {
+    pub fn build(
+        size: Vec2<i32>,
+        pixels: Option<&[u8]>,
+        filter: TextureFilter,
+    ) -> Result<Self, String> {
+        if let Some(pixels) = pixels {
+            if pixels.len() != (size.x * size.y * 4) as usize {
+                return Err("pixels length does not match texture size".to_string());
+            }
+        }
+
+        let mut id = 0;
+
+        // Génère la texture.
+        gl_exec!(|| gl::GenTextures(1, &mut id))?;
+
+        let texture = Self {
+            handle: GlObject::new(GlObjectKind::Texture, id),
+            size,
+        };
+
+        texture.bind(0)?;
+
+        // Si aucun pixel n'est donné, le contenu de la texture est indéfini, ce qui suffit pour
+        // une texture qui servira de cible de rendu.
+        gl_exec!(|| gl::TexImage2D(
+            gl::TEXTURE_2D,
+            0,
+            gl::RGBA8 as GLint,
+            texture.size.x as GLsizei,
+            texture.size.y as GLsizei,
+            0,
+            gl::RGBA,
+            gl::UNSIGNED_BYTE,
+            match pixels {
+                Some(p) => p.as_ptr() as *const os::raw::c_void,
+                None => ptr::null(),
+            },
+        ))?;
+
+        if pixels.is_some() {
+            stats::record_texture_upload();
+        }
+
+        gl_exec!(|| gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_WRAP_S,
+            gl::CLAMP_TO_EDGE as GLint
+        ))?;
+        gl_exec!(|| gl::TexParameteri(
+            gl::TEXTURE_2D,
+            gl::TEXTURE_WRAP_T,
+            gl::CLAMP_TO_EDGE as GLint
+        ))?;
+
+        texture.set_filter(filter)?;
+
+        gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, 0))?;
+        state_cache::record_direct_binding(DirectBinding::Texture);
+
+        Ok(texture)
+    }
+
+    pub fn bind(&self, unit: u32) -> Result<(), String> {
+        gl_exec!(|| gl::ActiveTexture(gl::TEXTURE0 + unit))?;
+        gl_exec!(|| gl::BindTexture(gl::TEXTURE_2D, self.handle.get_id()))?;
+        state_cache::record_direct_binding(DirectBinding::Texture);
+
+        Ok(())
+    }
+
+    pub fn set_filter(&self, filter: TextureFilter) -> Result<(), String> {
+        // La texture doit être active avant d'appeler cette fonction.
+        let value = filter.get_gl_filter() as GLint;
+
+        gl_exec!(|| gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, value))?;
+        gl_exec!(|| gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, value))
+    }
+
+    pub fn share(&self) -> Self {
+        Self {
+            handle: self.handle.share(),
+            size: self.size.clone(),
+        }
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.handle.set_label(label);
+    }
+
+    pub fn get_id(&self) -> GLuint {
+        self.handle.get_id()
+    }
+
+    pub fn get_size(&self) -> Vec2<i32> {
+        self.size.clone()
+    }
+}
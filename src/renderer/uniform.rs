@@ -1,33 +1,146 @@
-use std::ffi::CString;
-
-use gl::types::GLint;
+use gl::types::{GLenum, GLint, GLsizei, GLuint};
 
 use crate::{
     gl_exec,
     maths::{
         mat::Mat4,
-        vec::{Vec3, Vec4},
+        vec::{Vec2, Vec3, Vec4},
+    },
+    shader::{
+        program::ShaderProgram,
+        reflection::{is_sampler, type_name, ShaderVariable},
     },
-    shader::program::ShaderProgram,
 };
 
 #[derive(Clone)]
+pub enum UniformValue {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    Vec2(Vec2<f32>),
+    Vec3(Vec3<f32>),
+    Vec4(Vec4<f32>),
+    Mat4(Mat4<f32>),
+    IntArray(Vec<i32>),
+    FloatArray(Vec<f32>),
+    Vec2Array(Vec<Vec2<f32>>),
+    Vec3Array(Vec<Vec3<f32>>),
+    Vec4Array(Vec<Vec4<f32>>),
+    // Identifiant d'une texture OpenGL, liée à une unité de texture au moment de l'envoi.
+    Texture(GLuint),
+}
+
+impl UniformValue {
+    // Indique si la valeur peut être envoyée à une variable GLSL de ce type et de cette taille.
+    pub fn matches(&self, gl_type: GLenum, size: GLint) -> bool {
+        let (types, count): (&[GLenum], usize) = match self {
+            UniformValue::Bool(_) => (&[gl::BOOL, gl::INT], 1),
+            UniformValue::Int(_) => (&[gl::INT, gl::BOOL], 1),
+            UniformValue::Float(_) => (&[gl::FLOAT], 1),
+            UniformValue::Vec2(_) => (&[gl::FLOAT_VEC2], 1),
+            UniformValue::Vec3(_) => (&[gl::FLOAT_VEC3], 1),
+            UniformValue::Vec4(_) => (&[gl::FLOAT_VEC4], 1),
+            UniformValue::Mat4(_) => (&[gl::FLOAT_MAT4], 1),
+            UniformValue::IntArray(v) => (&[gl::INT, gl::BOOL], v.len()),
+            UniformValue::FloatArray(v) => (&[gl::FLOAT], v.len()),
+            UniformValue::Vec2Array(v) => (&[gl::FLOAT_VEC2], v.len()),
+            UniformValue::Vec3Array(v) => (&[gl::FLOAT_VEC3], v.len()),
+            UniformValue::Vec4Array(v) => (&[gl::FLOAT_VEC4], v.len()),
+            UniformValue::Texture(_) => return is_sampler(gl_type),
+        };
+
+        // Un entier peut aussi désigner l'unité de texture d'un sampler.
+        let int_as_sampler = matches!(self, UniformValue::Int(_)) && is_sampler(gl_type);
+
+        (types.contains(&gl_type) || int_as_sampler) && count <= size.max(1) as usize
+    }
+
+    pub fn get_type_name(&self) -> &'static str {
+        match self {
+            UniformValue::Bool(_) => "bool",
+            UniformValue::Int(_) => "int",
+            UniformValue::Float(_) => "float",
+            UniformValue::Vec2(_) => "vec2",
+            UniformValue::Vec3(_) => "vec3",
+            UniformValue::Vec4(_) => "vec4",
+            UniformValue::Mat4(_) => "mat4",
+            UniformValue::IntArray(_) => "int[]",
+            UniformValue::FloatArray(_) => "float[]",
+            UniformValue::Vec2Array(_) => "vec2[]",
+            UniformValue::Vec3Array(_) => "vec3[]",
+            UniformValue::Vec4Array(_) => "vec4[]",
+            UniformValue::Texture(_) => "texture",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct Uniform {
     location: GLint,
+    gl_type: GLenum,
+    size: GLint,
 }
 
 impl Uniform {
     pub fn new() -> Self {
-        Self { location: -1 }
+        Self {
+            location: -1,
+            gl_type: 0,
+            size: 0,
+        }
     }
 
+    // Échoue si la variable n'existe pas dans le programme ou a été retirée par le
+    // compilateur GLSL.
     pub fn search(&mut self, shader_program: &ShaderProgram, name: &str) -> Result<(), String> {
-        let c_str = match CString::new(name.as_bytes()) {
-            Ok(s) => s,
-            Err(e) => return Err(e.to_string()),
+        *self = Self::from_variable(&shader_program.get_uniform(name)?);
+
+        Ok(())
+    }
+
+    pub fn from_variable(variable: &ShaderVariable) -> Self {
+        Self {
+            location: variable.location,
+            gl_type: variable.gl_type,
+            size: variable.size,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.location != -1
+    }
+
+    pub fn check_value(&self, name: &str, value: &UniformValue) -> Result<(), String> {
+        if value.matches(self.gl_type, self.size) {
+            return Ok(());
+        }
+
+        let expected = if self.size > 1 {
+            format!("{}[{}]", type_name(self.gl_type), self.size)
+        } else {
+            type_name(self.gl_type).to_string()
         };
 
-        gl_exec!(|| self.location = gl::GetUniformLocation(shader_program.get_id(), c_str.as_ptr()))
+        Err(format!(
+            "uniform '{name}' is declared as {expected}, cannot send a {} value",
+            value.get_type_name()
+        ))
+    }
+
+    pub fn send_bool(&self, value: bool) -> Result<(), String> {
+        self.send_i32(value as i32)
+    }
+
+    pub fn send_i32(&self, value: i32) -> Result<(), String> {
+        gl_exec!(|| gl::Uniform1i(self.location, value))
+    }
+
+    pub fn send_f32(&self, value: f32) -> Result<(), String> {
+        gl_exec!(|| gl::Uniform1f(self.location, value))
+    }
+
+    pub fn send_vec2(&self, v: &Vec2<f32>) -> Result<(), String> {
+        gl_exec!(|| gl::Uniform2f(self.location, v.x, v.y))
     }
 
     pub fn send_vec3(&self, v: &Vec3<f32>) -> Result<(), String> {
@@ -41,4 +154,49 @@ impl Uniform {
     pub fn send_mat4(&self, m: &Mat4<f32>) -> Result<(), String> {
         gl_exec!(|| gl::UniformMatrix4fv(self.location, 1, gl::TRUE, m.borrow_data().as_ptr()))
     }
+
+    pub fn send_i32_array(&self, values: &[i32]) -> Result<(), String> {
+        gl_exec!(|| gl::Uniform1iv(self.location, values.len() as GLsizei, values.as_ptr()))
+    }
+
+    pub fn send_f32_array(&self, values: &[f32]) -> Result<(), String> {
+        gl_exec!(|| gl::Uniform1fv(self.location, values.len() as GLsizei, values.as_ptr()))
+    }
+
+    pub fn send_vec2_array(&self, values: &[Vec2<f32>]) -> Result<(), String> {
+        let data: Vec<f32> = values.iter().flat_map(|v| [v.x, v.y]).collect();
+
+        gl_exec!(|| gl::Uniform2fv(self.location, values.len() as GLsizei, data.as_ptr()))
+    }
+
+    pub fn send_vec3_array(&self, values: &[Vec3<f32>]) -> Result<(), String> {
+        let data: Vec<f32> = values.iter().flat_map(|v| [v.x, v.y, v.z]).collect();
+
+        gl_exec!(|| gl::Uniform3fv(self.location, values.len() as GLsizei, data.as_ptr()))
+    }
+
+    pub fn send_vec4_array(&self, values: &[Vec4<f32>]) -> Result<(), String> {
+        let data: Vec<f32> = values.iter().flat_map(|v| [v.x, v.y, v.z, v.w]).collect();
+
+        gl_exec!(|| gl::Uniform4fv(self.location, values.len() as GLsizei, data.as_ptr()))
+    }
+
+    // Les textures doivent être liées à l'unité `texture_unit` par l'appelant.
+    pub fn send_value(&self, value: &UniformValue, texture_unit: u32) -> Result<(), String> {
+        match value {
+            UniformValue::Bool(v) => self.send_bool(*v),
+            UniformValue::Int(v) => self.send_i32(*v),
+            UniformValue::Float(v) => self.send_f32(*v),
+            UniformValue::Vec2(v) => self.send_vec2(v),
+            UniformValue::Vec3(v) => self.send_vec3(v),
+            UniformValue::Vec4(v) => self.send_vec4(v),
+            UniformValue::Mat4(v) => self.send_mat4(v),
+            UniformValue::IntArray(v) => self.send_i32_array(v),
+            UniformValue::FloatArray(v) => self.send_f32_array(v),
+            UniformValue::Vec2Array(v) => self.send_vec2_array(v),
+            UniformValue::Vec3Array(v) => self.send_vec3_array(v),
+            UniformValue::Vec4Array(v) => self.send_vec4_array(v),
+            UniformValue::Texture(_) => self.send_i32(texture_unit as i32),
+        }
+    }
 }
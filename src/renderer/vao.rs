@@ -4,9 +4,14 @@ use gl::types::GLuint;
 
 use crate::gl_exec;
 
-#[derive(Clone)]
+use super::{
+    gl_object::{GlObject, GlObjectKind},
+    state_cache::{self, DirectBinding},
+    vertex_layout::{VertexAttrib, VertexLayout},
+};
+
 pub struct VAO {
-    id: GLuint,
+    handle: GlObject,
 }
 
 impl VAO {
@@ -17,11 +22,31 @@ impl VAO {
             return Err(err);
         }
 
-        Ok(Self { id })
+        Ok(Self {
+            handle: GlObject::new(GlObjectKind::VertexArray, id),
+        })
+    }
+
+    // Les deux VAO désignent le même objet OpenGL, supprimé avec le dernier.
+    pub fn share(&self) -> Self {
+        Self {
+            handle: self.handle.share(),
+        }
     }
 
     pub fn bind(&self) -> Result<(), String> {
-        gl_exec!(|| gl::BindVertexArray(self.id))
+        gl_exec!(|| gl::BindVertexArray(self.handle.get_id()))?;
+        state_cache::record_direct_binding(DirectBinding::VertexArray);
+
+        Ok(())
+    }
+
+    // Délie le VAO actif afin que les liaisons suivantes ne le modifient pas.
+    pub fn unbind() -> Result<(), String> {
+        gl_exec!(|| gl::BindVertexArray(0))?;
+        state_cache::record_direct_binding(DirectBinding::VertexArray);
+
+        Ok(())
     }
 
     pub fn attrib_pointer(
@@ -41,11 +66,55 @@ impl VAO {
         ))
     }
 
+    // Le VBO contenant les données doit être actif.
+    pub fn set_attrib(&self, attrib: &VertexAttrib, stride: usize) -> Result<(), String> {
+        let offset = attrib.offset as *const os::raw::c_void;
+
+        if attrib.integer {
+            gl_exec!(|| gl::VertexAttribIPointer(
+                attrib.index,
+                attrib.components,
+                attrib.attrib_type.get_gl_type(),
+                stride as i32,
+                offset,
+            ))?;
+        } else {
+            gl_exec!(|| gl::VertexAttribPointer(
+                attrib.index,
+                attrib.components,
+                attrib.attrib_type.get_gl_type(),
+                if attrib.normalized {
+                    gl::TRUE
+                } else {
+                    gl::FALSE
+                },
+                stride as i32,
+                offset,
+            ))?;
+        }
+
+        self.enable_attrib(attrib.index)?;
+
+        gl_exec!(|| gl::VertexAttribDivisor(attrib.index, attrib.divisor))
+    }
+
+    pub fn set_layout(&self, layout: &VertexLayout) -> Result<(), String> {
+        for attrib in layout.borrow_attribs().iter() {
+            self.set_attrib(attrib, layout.get_stride())?;
+        }
+
+        Ok(())
+    }
+
     pub fn enable_attrib(&self, index: u32) -> Result<(), String> {
         gl_exec!(|| gl::EnableVertexAttribArray(index))
     }
 
+    pub fn set_label(&self, label: &str) {
+        self.handle.set_label(label);
+    }
+
     pub fn get_id(&self) -> GLuint {
-        self.id
+        self.handle.get_id()
     }
 }
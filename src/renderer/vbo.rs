@@ -1,57 +1,222 @@
-use std::ptr;
+use std::{cell::Cell, ops::Range, os, ptr, rc::Rc};
 
-use gl::types::GLuint;
+use gl::types::{GLenum, GLuint};
 
 use crate::gl_exec;
 
-#[derive(Clone)]
+use super::{
+    gl_object::{GlObject, GlObjectKind},
+    state_cache::{self, DirectBinding},
+};
+
 pub struct VBO {
-    vertices: Vec<f32>,
-    id: GLuint,
+    handle: GlObject,
+    // Taille allouée en octets, partagée avec les copies puisqu'elles désignent le même tampon.
+    size: Rc<Cell<usize>>,
+    // Incrémentée à chaque écriture, permet à un utilisateur de savoir si une copie a remplacé
+    // le contenu.
+    revision: Rc<Cell<u64>>,
+    type_: VBOType,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum VBOType {
     StaticDraw,
     DynamicDraw,
+    // Données réécrites à chaque image, voir `VBO::stream`.
+    StreamDraw,
+}
+
+impl VBOType {
+    pub fn get_gl_usage(&self) -> GLenum {
+        match self {
+            VBOType::StaticDraw => gl::STATIC_DRAW,
+            VBOType::DynamicDraw => gl::DYNAMIC_DRAW,
+            VBOType::StreamDraw => gl::STREAM_DRAW,
+        }
+    }
+}
+
+// Vue en octets d'un tableau de flottants, tel qu'envoyé à OpenGL.
+pub fn f32_bytes(data: &[f32]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
 }
 
 impl VBO {
-    pub fn build(mut vertices: Vec<f32>, size: isize, type_: VBOType) -> Result<Self, String> {
+    // `size` est en octets et peut dépasser la taille de `vertices`, le reste est alors réservé
+    // sans être initialisé.
+    pub fn build(vertices: Vec<f32>, size: isize, type_: VBOType) -> Result<Self, String> {
+        let data = f32_bytes(&vertices);
+
+        if (size as usize) < data.len() {
+            return Err(format!(
+                "vbo size ({size}) is smaller than its vertices ({})",
+                data.len()
+            ));
+        }
+
+        let vbo = Self::generate(type_)?;
+
+        vbo.allocate(size as usize)?;
+        vbo.write(0, data)?;
+
+        Ok(vbo)
+    }
+
+    // Sommets déjà encodés selon un `VertexLayout`.
+    pub fn build_bytes(data: &[u8], type_: VBOType) -> Result<Self, String> {
+        let vbo = Self::generate(type_)?;
+
+        gl_exec!(|| gl::BufferData(
+            gl::ARRAY_BUFFER,
+            data.len() as isize,
+            if !data.is_empty() {
+                data.as_ptr() as *const os::raw::c_void
+            } else {
+                ptr::null()
+            },
+            type_.get_gl_usage()
+        ))?;
+
+        vbo.size.set(data.len());
+        vbo.bump_revision();
+
+        Ok(vbo)
+    }
+
+    fn generate(type_: VBOType) -> Result<Self, String> {
         let mut id = 0;
 
         // Génère un nouveau VBO.
         gl_exec!(|| gl::GenBuffers(1, &mut id))?;
 
+        let handle = GlObject::new(GlObjectKind::Buffer, id);
+
         // Rend le nouveau VBO actif.
         gl_exec!(|| gl::BindBuffer(gl::ARRAY_BUFFER, id))?;
+        state_cache::record_direct_binding(DirectBinding::ArrayBuffer);
+
+        Ok(Self {
+            handle,
+            size: Rc::new(Cell::new(0)),
+            revision: Rc::new(Cell::new(0)),
+            type_,
+        })
+    }
+
+    pub fn share(&self) -> Self {
+        Self {
+            handle: self.handle.share(),
+            size: Rc::clone(&self.size),
+            revision: Rc::clone(&self.revision),
+            type_: self.type_,
+        }
+    }
+
+    pub fn bind(&self) -> Result<(), String> {
+        gl_exec!(|| gl::BindBuffer(gl::ARRAY_BUFFER, self.handle.get_id()))?;
+        state_cache::record_direct_binding(DirectBinding::ArrayBuffer);
 
-        // Copie les données dans le VBO nouvellement créé.
+        Ok(())
+    }
+
+    // Remplace les octets `range` du tampon, qui doit déjà être assez grand.
+    pub fn update(&self, range: Range<usize>, data: &[u8]) -> Result<(), String> {
+        self.bind()?;
+        self.update_bound(range, data)
+    }
+
+    // Comme `update`, lorsque le tampon est déjà lié à `GL_ARRAY_BUFFER`.
+    pub fn update_bound(&self, range: Range<usize>, data: &[u8]) -> Result<(), String> {
+        if range.len() != data.len() {
+            return Err(format!(
+                "vbo update range ({}..{}) does not match the data size ({})",
+                range.start,
+                range.end,
+                data.len()
+            ));
+        }
+
+        if range.end > self.size.get() {
+            return Err(format!(
+                "vbo update range ({}..{}) exceeds the buffer size ({})",
+                range.start,
+                range.end,
+                self.size.get()
+            ));
+        }
+
+        self.write(range.start, data)
+    }
+
+    // Réalloue le tampon avec `size` octets, son contenu précédent est perdu.
+    pub fn resize(&self, size: usize) -> Result<(), String> {
+        self.bind()?;
+        self.allocate(size)
+    }
+
+    // Remplace tout le contenu du tampon. L'ancien stockage est d'abord abandonné (orphaning) :
+    // le pilote en fournit un neuf au lieu d'attendre que les rendus en cours aient fini de lire
+    // l'ancien, ce qui convient aux données réécrites à chaque image.
+    pub fn stream(&self, data: &[u8]) -> Result<(), String> {
+        self.bind()?;
+        self.allocate(self.size.get().max(data.len()))?;
+        self.write(0, data)
+    }
+
+    fn allocate(&self, size: usize) -> Result<(), String> {
         gl_exec!(|| gl::BufferData(
             gl::ARRAY_BUFFER,
-            size,
-            if !vertices.is_empty() {
-                vertices.as_mut_ptr() as *const std::os::raw::c_void
-            } else {
-                ptr::null_mut()
-            },
-            match type_ {
-                VBOType::StaticDraw => gl::STATIC_DRAW,
-                VBOType::DynamicDraw => gl::DYNAMIC_DRAW,
-            }
+            size as isize,
+            ptr::null(),
+            self.type_.get_gl_usage()
         ))?;
 
-        Ok(Self { vertices, id })
+        self.size.set(size);
+        self.bump_revision();
+
+        Ok(())
     }
 
-    pub fn bind(&self) -> Result<(), String> {
-        gl_exec!(|| gl::BindBuffer(gl::ARRAY_BUFFER, self.id))
+    // Le tampon doit être actif.
+    fn write(&self, offset: usize, data: &[u8]) -> Result<(), String> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        gl_exec!(|| gl::BufferSubData(
+            gl::ARRAY_BUFFER,
+            offset as isize,
+            data.len() as isize,
+            data.as_ptr() as *const os::raw::c_void
+        ))?;
+
+        self.bump_revision();
+
+        Ok(())
+    }
+
+    fn bump_revision(&self) {
+        self.revision.set(self.revision.get() + 1);
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.handle.set_label(label);
     }
 
     pub fn get_id(&self) -> GLuint {
-        self.id
+        self.handle.get_id()
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size.get()
+    }
+
+    pub fn get_revision(&self) -> u64 {
+        self.revision.get()
     }
 
-    pub fn borrow_vertices(&self) -> &Vec<f32> {
-        &self.vertices
+    pub fn get_type(&self) -> VBOType {
+        self.type_
     }
 }
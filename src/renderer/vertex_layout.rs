@@ -0,0 +1,141 @@
+use gl::types::GLenum;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttribType {
+    U8,
+    U16,
+    I32,
+    F32,
+}
+
+impl AttribType {
+    pub fn get_gl_type(&self) -> GLenum {
+        match self {
+            AttribType::U8 => gl::UNSIGNED_BYTE,
+            AttribType::U16 => gl::UNSIGNED_SHORT,
+            AttribType::I32 => gl::INT,
+            AttribType::F32 => gl::FLOAT,
+        }
+    }
+
+    pub fn get_size(&self) -> usize {
+        match self {
+            AttribType::U8 => 1,
+            AttribType::U16 => 2,
+            AttribType::I32 | AttribType::F32 => 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct VertexAttrib {
+    // Emplacement dans le shader (`layout (location = index)`).
+    pub index: u32,
+    pub components: i32,
+    pub attrib_type: AttribType,
+    // Les entiers sont ramenés entre 0 et 1 au lieu d'être convertis tels quels.
+    pub normalized: bool,
+    // Lu comme un entier par le shader (`ivec`, `uvec`) au lieu d'un flottant.
+    pub integer: bool,
+    // 0 : avance à chaque sommet, n : avance toutes les n instances.
+    pub divisor: u32,
+    // Décalage en octets depuis le début d'un sommet.
+    pub offset: usize,
+}
+
+// Description des sommets d'un VBO, construite attribut par attribut :
+// `VertexLayout::new().attrib(0, 2, AttribType::F32).attrib(1, 4, AttribType::U8).normalized()`.
+#[derive(Clone, Debug, Default)]
+pub struct VertexLayout {
+    attribs: Vec<VertexAttrib>,
+    stride: usize,
+}
+
+impl VertexLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Ajoute un attribut placé juste après le précédent.
+    pub fn attrib(mut self, index: u32, components: i32, attrib_type: AttribType) -> Self {
+        self.attribs.push(VertexAttrib {
+            index,
+            components,
+            attrib_type,
+            normalized: false,
+            integer: false,
+            divisor: 0,
+            offset: self.stride,
+        });
+
+        self.stride += components as usize * attrib_type.get_size();
+
+        self
+    }
+
+    // Les modificateurs suivants s'appliquent au dernier attribut ajouté.
+    pub fn normalized(mut self) -> Self {
+        if let Some(attrib) = self.attribs.last_mut() {
+            attrib.normalized = true;
+        }
+
+        self
+    }
+
+    pub fn integer(mut self) -> Self {
+        if let Some(attrib) = self.attribs.last_mut() {
+            attrib.integer = true;
+        }
+
+        self
+    }
+
+    pub fn divisor(mut self, divisor: u32) -> Self {
+        if let Some(attrib) = self.attribs.last_mut() {
+            attrib.divisor = divisor;
+        }
+
+        self
+    }
+
+    // Force la taille d'un sommet, utile lorsque les données contiennent du remplissage.
+    pub fn stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+
+        self
+    }
+
+    pub fn get_stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn borrow_attribs(&self) -> &Vec<VertexAttrib> {
+        &self.attribs
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Topology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    #[default]
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+}
+
+impl Topology {
+    pub fn get_gl_mode(&self) -> GLenum {
+        match self {
+            Topology::Points => gl::POINTS,
+            Topology::Lines => gl::LINES,
+            Topology::LineStrip => gl::LINE_STRIP,
+            Topology::LineLoop => gl::LINE_LOOP,
+            Topology::Triangles => gl::TRIANGLES,
+            Topology::TriangleStrip => gl::TRIANGLE_STRIP,
+            Topology::TriangleFan => gl::TRIANGLE_FAN,
+        }
+    }
+}
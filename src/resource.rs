@@ -10,7 +10,8 @@ use crate::{
     drawing::{rectangle::Rectangle, text::Text},
     maths::vec::{Vec3, Vec4},
     renderer::draw::Draw,
-    shader::{program::ShaderProgram, Shader, ShaderType},
+    resource::gl_resource::ShaderProgramResource,
+    shader::registry::{ShaderRegistry, ShaderSource},
 };
 
 pub mod gl_resource;
@@ -54,6 +55,10 @@ impl Resource {
         );
     }
 
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
     pub fn get_ref<T: Any>(&self, unique_id: &str) -> Option<Ref<'_, T>> {
         // On retourne un T et non pas un &T car il est dans stocké dans une structure 'Ref', qui
         // est l'équivalent d'un &, donc il n'est pas nécessaire d'utiliser &T.
@@ -132,63 +137,57 @@ impl Resource {
     pub fn init_basic_resources(
         &mut self,
         drawing_objects: &mut Vec<Box<dyn Draw>>,
+        shaders: &mut ShaderRegistry,
     ) -> Result<(), String> {
         let basic_2d_vertex_shader = include_str!("../Builtin/Shaders/basic_2D_vertex_shader.glsl");
         let basic_2d_fragment_shader =
             include_str!("../Builtin/Shaders/basic_2D_fragment_shader.glsl");
 
-        let basic_text_vertex_shader =
-            include_str!("../Builtin/Shaders/basic_text_vertex_shader.glsl");
-        let basic_text_fragment_shader =
-            include_str!("../Builtin/Shaders/basic_text_fragment_shader.glsl");
-
-        let mut vert_shader = Shader::new(
-            ShaderType::Vertex,
-            "basic_2D_vertex_shader".to_string(),
-            basic_2d_vertex_shader.to_string(),
-        );
-
-        vert_shader.create()?;
-        vert_shader.source()?;
-        vert_shader.compile()?;
-
-        let mut frag_shader = Shader::new(
-            ShaderType::Fragment,
-            "basic_2D_fragment_shader".to_string(),
-            basic_2d_fragment_shader.to_string(),
-        );
+        let program = shaders.load(
+            "basic_2D",
+            ShaderSource::embedded("basic_2D_vertex_shader", basic_2d_vertex_shader),
+            ShaderSource::embedded("basic_2D_fragment_shader", basic_2d_fragment_shader),
+        )?;
 
-        frag_shader.create()?;
-        frag_shader.source()?;
-        frag_shader.compile()?;
+        // Le texte et les sprites sont des permutations du même programme.
+        let text_program = shaders.get_variant(
+            "basic_2D",
+            &[
+                ("PUNK_TEXTURE", "1"),
+                ("PUNK_ALPHA_TEXTURE", "1"),
+                ("PUNK_VERTEX_COLOR", "1"),
+            ],
+        )?;
 
-        let program = ShaderProgram::build(&vert_shader, &frag_shader)?;
+        // Pour les polices en mode `GlyphMode::Sdf`.
+        let sdf_text_program = shaders.get_variant(
+            "basic_2D",
+            &[
+                ("PUNK_TEXTURE", "1"),
+                ("PUNK_SDF", "1"),
+                ("PUNK_VERTEX_COLOR", "1"),
+            ],
+        )?;
 
-        program.link()?;
+        let texture_program = shaders.get_variant("basic_2D", &[("PUNK_TEXTURE", "1")])?;
 
-        let mut text_vert_shader = Shader::new(
-            ShaderType::Vertex,
-            "basic_text_vertex_shader".to_string(),
-            basic_text_vertex_shader.to_string(),
+        // Rend les programmes intégrés accessibles afin de pouvoir créer d'autres objets.
+        self.add(
+            "basic_2D_shader_program",
+            ShaderProgramResource(program.clone()),
         );
-
-        text_vert_shader.create()?;
-        text_vert_shader.source()?;
-        text_vert_shader.compile()?;
-
-        let mut text_frag_shader = Shader::new(
-            ShaderType::Fragment,
-            "basic_text_fragment_shader".to_string(),
-            basic_text_fragment_shader.to_string(),
+        self.add(
+            "basic_text_shader_program",
+            ShaderProgramResource(text_program),
+        );
+        self.add(
+            "sdf_text_shader_program",
+            ShaderProgramResource(sdf_text_program),
+        );
+        self.add(
+            "basic_texture_shader_program",
+            ShaderProgramResource(texture_program),
         );
-
-        text_frag_shader.create()?;
-        text_frag_shader.source()?;
-        text_frag_shader.compile()?;
-
-        let text_program = ShaderProgram::build(&text_vert_shader, &text_frag_shader)?;
-
-        text_program.link()?;
 
         let rect = Rectangle::build(
             program,
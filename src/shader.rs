@@ -2,8 +2,12 @@ use std::{ffi::CString, ptr};
 
 use crate::common::PunkString;
 use crate::gl_exec;
+use crate::renderer::gl_object::{GlObject, GlObjectKind};
 
+pub mod preprocessor;
 pub mod program;
+pub mod reflection;
+pub mod registry;
 
 pub enum ShaderType {
     Vertex,
@@ -14,7 +18,9 @@ pub struct Shader {
     shader_type: ShaderType,
     name: String,
     source: String,
-    id: u32,
+    // Le shader est supprimé avec sa dernière poignée, OpenGL le garde tant qu'il est attaché
+    // à un programme.
+    handle: GlObject,
 }
 
 impl Shader {
@@ -23,7 +29,7 @@ impl Shader {
             shader_type,
             name,
             source,
-            id: 0,
+            handle: GlObject::none(GlObjectKind::Shader),
         }
     }
 
@@ -33,7 +39,13 @@ impl Shader {
             ShaderType::Fragment => gl::FRAGMENT_SHADER,
         };
 
-        gl_exec!(|| self.id = gl::CreateShader(t))
+        let mut id = 0;
+
+        gl_exec!(|| id = gl::CreateShader(t))?;
+
+        self.handle = GlObject::new(GlObjectKind::Shader, id);
+
+        Ok(())
     }
 
     pub fn source(&self) -> Result<(), String> {
@@ -42,11 +54,11 @@ impl Shader {
             Err(e) => return Err(e.to_string()),
         };
 
-        gl_exec!(|| gl::ShaderSource(self.id, 1, &c_str.as_ptr(), ptr::null()))
+        gl_exec!(|| gl::ShaderSource(self.get_id(), 1, &c_str.as_ptr(), ptr::null()))
     }
 
     pub fn compile(&self) -> Result<(), String> {
-        if let Err(err) = gl_exec!(|| gl::CompileShader(self.id)) {
+        if let Err(err) = gl_exec!(|| gl::CompileShader(self.get_id())) {
             return Err(err);
         }
 
@@ -54,13 +66,15 @@ impl Shader {
         let mut info_log: [i8; 512] = [0; 512];
 
         // S'il y a une erreur, 'success' vaudra 0.
-        if let Err(err) = gl_exec!(|| gl::GetShaderiv(self.id, gl::COMPILE_STATUS, &mut success)) {
+        if let Err(err) =
+            gl_exec!(|| gl::GetShaderiv(self.get_id(), gl::COMPILE_STATUS, &mut success))
+        {
             return Err(err);
         }
 
         if success == 0 {
             if let Err(err) = gl_exec!(|| gl::GetShaderInfoLog(
-                self.id,
+                self.get_id(),
                 info_log.len() as i32,
                 ptr::null_mut(),
                 info_log.as_mut_ptr(),
@@ -74,8 +88,8 @@ impl Shader {
         Ok(())
     }
 
-    pub fn destroy(&self) -> Result<(), String> {
-        gl_exec!(|| gl::DeleteShader(self.id))
+    pub fn get_id(&self) -> u32 {
+        self.handle.get_id()
     }
 
     pub fn is_vertex_shader(&self) -> bool {
@@ -0,0 +1,299 @@
+use std::collections::{BTreeMap, HashMap};
+
+// Jeu de `#define` d'une permutation, trié afin de servir de clé de cache.
+pub type ShaderDefines = Vec<(String, String)>;
+
+pub fn normalize_defines(defines: &[(&str, &str)]) -> ShaderDefines {
+    // En cas de doublon, la dernière valeur donnée l'emporte.
+    let normalized: BTreeMap<String, String> = defines
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    normalized.into_iter().collect()
+}
+
+// Résout les `#include "fichier"` à partir d'un ensemble de fichiers virtuels et injecte des
+// `#define` juste après la directive `#version`.
+pub struct ShaderPreprocessor {
+    files: HashMap<String, String>,
+}
+
+impl Default for ShaderPreprocessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> Self {
+        let mut preprocessor = Self {
+            files: HashMap::new(),
+        };
+
+        preprocessor.add_file(
+            "punk_common.glsl",
+            include_str!("../../Builtin/Shaders/punk_common.glsl"),
+        );
+
+        preprocessor
+    }
+
+    pub fn add_file(&mut self, name: &str, source: &str) {
+        self.files.insert(name.to_string(), source.to_string());
+    }
+
+    pub fn remove_file(&mut self, name: &str) -> Option<String> {
+        self.files.remove(name)
+    }
+
+    pub fn has_file(&self, name: &str) -> bool {
+        self.files.contains_key(name)
+    }
+
+    pub fn process(&self, source: &str, defines: &ShaderDefines) -> Result<String, String> {
+        let mut output = String::new();
+        let mut included = Vec::new();
+        let mut version_found = false;
+        let mut in_comment = false;
+
+        for (index, line) in source.lines().enumerate() {
+            let trimmed = line.trim_start();
+
+            // Les directives à l'intérieur d'un commentaire `/* */` sont gardées telles quelles.
+            let commented = in_comment;
+            in_comment = Self::ends_in_comment(line, in_comment);
+
+            if commented {
+                output.push_str(line);
+                output.push('\n');
+
+                continue;
+            }
+
+            if !version_found && trimmed.starts_with("#version") {
+                version_found = true;
+
+                output.push_str(line);
+                output.push('\n');
+
+                for (name, value) in defines.iter() {
+                    output.push_str(&format!("#define {name} {value}\n"));
+                }
+
+                // Garde les numéros de ligne des erreurs de compilation alignés sur le fichier.
+                if !defines.is_empty() {
+                    output.push_str(&format!("#line {}\n", index + 2));
+                }
+
+                continue;
+            }
+
+            if let Some(name) = Self::parse_include(trimmed)? {
+                self.include(&name, &mut output, &mut included, &mut Vec::new())?;
+
+                output.push_str(&format!("#line {}\n", index + 2));
+
+                continue;
+            }
+
+            output.push_str(line);
+            output.push('\n');
+        }
+
+        if !version_found && !defines.is_empty() {
+            return Err("cannot inject defines in a shader without #version".to_string());
+        }
+
+        Ok(output)
+    }
+
+    // Un fichier n'est inclus qu'une seule fois par shader, les inclusions circulaires sont
+    // signalées.
+    fn include(
+        &self,
+        name: &str,
+        output: &mut String,
+        included: &mut Vec<String>,
+        stack: &mut Vec<String>,
+    ) -> Result<(), String> {
+        if stack.iter().any(|file| file == name) {
+            return Err(format!(
+                "circular include: {} -> {name}",
+                stack.join(" -> ")
+            ));
+        }
+
+        if included.iter().any(|file| file == name) {
+            return Ok(());
+        }
+
+        let source = self
+            .files
+            .get(name)
+            .ok_or(format!("included file '{name}' not found"))?;
+
+        included.push(name.to_string());
+        stack.push(name.to_string());
+
+        let mut in_comment = false;
+
+        for line in source.lines() {
+            let commented = in_comment;
+            in_comment = Self::ends_in_comment(line, in_comment);
+
+            let directive = if commented {
+                None
+            } else {
+                Self::parse_include(line.trim_start())?
+            };
+
+            match directive {
+                Some(child) => self.include(&child, output, included, stack)?,
+                None => {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+
+        stack.pop();
+
+        Ok(())
+    }
+
+    // Retourne vrai si la ligne se termine à l'intérieur d'un commentaire `/* */`, `in_comment`
+    // indiquant si elle commence dans l'un d'eux.
+    fn ends_in_comment(line: &str, mut in_comment: bool) -> bool {
+        let mut rest = line;
+
+        loop {
+            if in_comment {
+                match rest.find("*/") {
+                    Some(end) => {
+                        rest = &rest[end + 2..];
+                        in_comment = false;
+                    }
+                    None => return true,
+                }
+            } else {
+                let line_comment = rest.find("//");
+
+                match rest.find("/*") {
+                    Some(start) if line_comment.is_none_or(|comment| start < comment) => {
+                        rest = &rest[start + 2..];
+                        in_comment = true;
+                    }
+                    _ => return false,
+                }
+            }
+        }
+    }
+
+    fn parse_include(line: &str) -> Result<Option<String>, String> {
+        let rest = match line.strip_prefix("#include") {
+            Some(rest) => rest.trim(),
+            None => return Ok(None),
+        };
+
+        let name = rest
+            .strip_prefix('"')
+            .and_then(|rest| rest.strip_suffix('"'))
+            .ok_or(format!("malformed include directive: {line}"))?;
+
+        Ok(Some(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preprocessor(files: &[(&str, &str)]) -> ShaderPreprocessor {
+        let mut preprocessor = ShaderPreprocessor::new();
+
+        for (name, source) in files {
+            preprocessor.add_file(name, source);
+        }
+
+        preprocessor
+    }
+
+    #[test]
+    fn test_nested_include() {
+        let preprocessor = preprocessor(&[
+            ("a.glsl", "#include \"b.glsl\"\nfloat a;"),
+            ("b.glsl", "float b;"),
+        ]);
+
+        let output = preprocessor
+            .process("#include \"a.glsl\"\nvoid main() {}", &Vec::new())
+            .unwrap();
+
+        assert_eq!(output, "float b;\nfloat a;\n#line 2\nvoid main() {}\n");
+    }
+
+    #[test]
+    fn test_duplicate_include_skipped() {
+        let preprocessor = preprocessor(&[
+            ("a.glsl", "#include \"c.glsl\"\nfloat a;"),
+            ("c.glsl", "float c;"),
+        ]);
+
+        let output = preprocessor
+            .process("#include \"a.glsl\"\n#include \"c.glsl\"", &Vec::new())
+            .unwrap();
+
+        assert_eq!(output.matches("float c;").count(), 1);
+    }
+
+    #[test]
+    fn test_circular_include() {
+        let preprocessor = preprocessor(&[
+            ("a.glsl", "#include \"b.glsl\""),
+            ("b.glsl", "#include \"a.glsl\""),
+        ]);
+
+        let error = preprocessor
+            .process("#include \"a.glsl\"", &Vec::new())
+            .unwrap_err();
+
+        assert_eq!(error, "circular include: a.glsl -> b.glsl -> a.glsl");
+    }
+
+    #[test]
+    fn test_missing_include() {
+        let preprocessor = preprocessor(&[]);
+
+        let error = preprocessor
+            .process("#include \"missing.glsl\"", &Vec::new())
+            .unwrap_err();
+
+        assert_eq!(error, "included file 'missing.glsl' not found");
+    }
+
+    #[test]
+    fn test_include_in_block_comment() {
+        let preprocessor = preprocessor(&[("a.glsl", "/* début\n#include \"missing.glsl\"\n*/")]);
+
+        let source = "/*\n#include \"missing.glsl\"\n*/\n#include \"a.glsl\"";
+
+        assert!(preprocessor.process(source, &Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn test_defines_after_version() {
+        let preprocessor = preprocessor(&[]);
+
+        let defines = normalize_defines(&[("PUNK_TEXTURE", "1"), ("PUNK_ALPHA_TEST", "0.5")]);
+
+        let output = preprocessor
+            .process("// en-tête\n#version 330 core\nvoid main() {}", &defines)
+            .unwrap();
+
+        assert_eq!(
+            output,
+            "// en-tête\n#version 330 core\n#define PUNK_ALPHA_TEST 0.5\n#define PUNK_TEXTURE 1\n#line 3\nvoid main() {}\n"
+        );
+    }
+}
@@ -1,20 +1,46 @@
-use std::ptr;
+use std::{
+    cell::{Ref, RefCell},
+    ptr,
+    rc::Rc,
+};
 
-use gl::types::GLuint;
+use gl::types::{GLsizei, GLuint};
 
 use crate::common::PunkString;
 use crate::gl_exec;
+use crate::renderer::{
+    gl_object::{GlObject, GlObjectKind},
+    state_cache::{self, DirectBinding},
+};
 
-use super::Shader;
+use super::{
+    reflection::{ShaderReflection, ShaderVariable},
+    Shader, ShaderType,
+};
 
-#[derive(Clone)]
 pub struct ShaderProgram {
-    id: GLuint,
+    handle: GlObject,
+    // Rempli par `link`, partagé entre les copies du programme pour suivre les rechargements.
+    reflection: Rc<RefCell<ShaderReflection>>,
+}
+
+// Les copies partagent le même programme OpenGL et sa réflexion, le programme est supprimé avec
+// la dernière copie.
+impl Clone for ShaderProgram {
+    fn clone(&self) -> Self {
+        Self {
+            handle: self.handle.share(),
+            reflection: Rc::clone(&self.reflection),
+        }
+    }
 }
 
 impl ShaderProgram {
     pub fn none() -> Self {
-        Self { id: 0 }
+        Self {
+            handle: GlObject::none(GlObjectKind::Program),
+            reflection: Rc::new(RefCell::new(ShaderReflection::default())),
+        }
     }
 
     pub fn build(vertex_shader: &Shader, fragment_shader: &Shader) -> Result<Self, String> {
@@ -32,32 +58,144 @@ impl ShaderProgram {
             return Err(err);
         }
 
-        if let Err(err) = gl_exec!(|| gl::AttachShader(id, vertex_shader.id)) {
+        let program = ShaderProgram {
+            handle: GlObject::new(GlObjectKind::Program, id),
+            reflection: Rc::new(RefCell::new(ShaderReflection::default())),
+        };
+
+        if let Err(err) = gl_exec!(|| gl::AttachShader(id, vertex_shader.get_id())) {
             return Err(err);
         }
 
-        if let Err(err) = gl_exec!(|| gl::AttachShader(id, fragment_shader.id)) {
+        if let Err(err) = gl_exec!(|| gl::AttachShader(id, fragment_shader.get_id())) {
             return Err(err);
         }
 
-        Ok(ShaderProgram { id })
+        Ok(program)
+    }
+
+    // Compile les deux shaders puis lie le programme.
+    pub fn from_sources(
+        vertex_name: &str,
+        vertex_source: &str,
+        fragment_name: &str,
+        fragment_source: &str,
+    ) -> Result<Self, String> {
+        let (vert_shader, frag_shader) =
+            Self::compile_shaders(vertex_name, vertex_source, fragment_name, fragment_source)?;
+
+        let program = Self::build(&vert_shader, &frag_shader)?;
+
+        program.link()?;
+
+        Ok(program)
+    }
+
+    fn compile_shaders(
+        vertex_name: &str,
+        vertex_source: &str,
+        fragment_name: &str,
+        fragment_source: &str,
+    ) -> Result<(Shader, Shader), String> {
+        let mut vert_shader = Shader::new(
+            ShaderType::Vertex,
+            vertex_name.to_string(),
+            vertex_source.to_string(),
+        );
+
+        vert_shader.create()?;
+        vert_shader.source()?;
+
+        if let Err(err) = vert_shader.compile() {
+            return Err(format!("{vertex_name}: {err}"));
+        }
+
+        let mut frag_shader = Shader::new(
+            ShaderType::Fragment,
+            fragment_name.to_string(),
+            fragment_source.to_string(),
+        );
+
+        frag_shader.create()?;
+        frag_shader.source()?;
+
+        if let Err(err) = frag_shader.compile() {
+            return Err(format!("{fragment_name}: {err}"));
+        }
+
+        Ok((vert_shader, frag_shader))
+    }
+
+    // Recompile le programme à partir de nouvelles sources en gardant le même identifiant, les
+    // copies existantes (matériaux, objets...) utilisent donc directement la nouvelle version.
+    // En cas d'erreur l'ancien programme reste intact.
+    pub fn relink(
+        &self,
+        vertex_name: &str,
+        vertex_source: &str,
+        fragment_name: &str,
+        fragment_source: &str,
+    ) -> Result<(), String> {
+        let (vert_shader, frag_shader) =
+            Self::compile_shaders(vertex_name, vertex_source, fragment_name, fragment_source)?;
+
+        // Un échec d'édition des liens invaliderait le programme actuel, on essaie donc d'abord
+        // sur un programme temporaire.
+        let program = Self::build(&vert_shader, &frag_shader)?;
+
+        program.link()?;
+
+        self.relink_with(&program)
+    }
+
+    // Remplace les shaders du programme par ceux d'un programme déjà lié avec succès, puis le
+    // relie en gardant le même identifiant.
+    pub fn relink_with(&self, program: &ShaderProgram) -> Result<(), String> {
+        let id = self.get_id();
+
+        // Les anciens shaders ont déjà été marqués pour suppression, les détacher les libère.
+        for shader in self.get_attached_shaders()? {
+            gl_exec!(|| gl::DetachShader(id, shader))?;
+        }
+
+        for shader in program.get_attached_shaders()? {
+            gl_exec!(|| gl::AttachShader(id, shader))?;
+        }
+
+        self.link()
+    }
+
+    fn get_attached_shaders(&self) -> Result<Vec<GLuint>, String> {
+        let mut count = 0;
+        let mut attached = [0 as GLuint; 8];
+
+        gl_exec!(|| gl::GetAttachedShaders(
+            self.get_id(),
+            attached.len() as GLsizei,
+            &mut count,
+            attached.as_mut_ptr()
+        ))?;
+
+        Ok(attached[..count as usize].to_vec())
     }
 
     pub fn link(&self) -> Result<(), String> {
-        if let Err(err) = gl_exec!(|| gl::LinkProgram(self.id)) {
+        if let Err(err) = gl_exec!(|| gl::LinkProgram(self.get_id())) {
             return Err(err);
         }
 
         let mut success = 0;
         let mut info_log: [i8; 512] = [0; 512];
 
-        if let Err(err) = gl_exec!(|| gl::GetProgramiv(self.id, gl::LINK_STATUS, &mut success)) {
+        if let Err(err) =
+            gl_exec!(|| gl::GetProgramiv(self.get_id(), gl::LINK_STATUS, &mut success))
+        {
             return Err(err);
         }
 
         if success == 0 {
             if let Err(err) = gl_exec!(|| gl::GetProgramInfoLog(
-                self.id,
+                self.get_id(),
                 512,
                 ptr::null_mut(),
                 info_log.as_mut_ptr()
@@ -68,14 +206,50 @@ impl ShaderProgram {
             return Err(PunkString::i8_array_to_string(&info_log));
         }
 
+        *self.reflection.borrow_mut() = ShaderReflection::query(self.get_id())?;
+
         Ok(())
     }
 
     pub fn use_it(&self) -> Result<(), String> {
-        gl_exec!(|| gl::UseProgram(self.id))
+        gl_exec!(|| gl::UseProgram(self.get_id()))?;
+        state_cache::record_direct_binding(DirectBinding::Program);
+
+        Ok(())
+    }
+
+    pub fn set_label(&self, label: &str) {
+        self.handle.set_label(label);
     }
 
     pub fn get_id(&self) -> GLuint {
-        self.id
+        self.handle.get_id()
+    }
+
+    pub fn borrow_reflection(&self) -> Ref<'_, ShaderReflection> {
+        self.reflection.borrow()
+    }
+
+    // Contrairement à `glGetUniformLocation`, une faute de frappe donne une erreur explicite.
+    pub fn get_uniform(&self, name: &str) -> Result<ShaderVariable, String> {
+        self.reflection.borrow().get_uniform(name).cloned().ok_or_else(|| {
+            format!(
+                "'{name}' is not an active uniform of shader program {} (misspelled or optimized out)",
+                self.get_id()
+            )
+        })
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Result<ShaderVariable, String> {
+        self.reflection
+            .borrow()
+            .get_attribute(name)
+            .cloned()
+            .ok_or_else(|| {
+                format!(
+                    "'{name}' is not an active attribute of shader program {}",
+                    self.get_id()
+                )
+            })
     }
 }
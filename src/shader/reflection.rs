@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use gl::types::{GLchar, GLenum, GLint, GLsizei, GLuint};
+
+use crate::gl_exec;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VariableKind {
+    Uniform,
+    Attribute,
+}
+
+// Variable active d'un programme telle que décrite par OpenGL après l'édition des liens.
+#[derive(Clone, Debug)]
+pub struct ShaderVariable {
+    pub name: String,
+    pub gl_type: GLenum,
+    // Nombre d'éléments, supérieur à 1 pour les tableaux.
+    pub size: GLint,
+    pub location: GLint,
+}
+
+impl ShaderVariable {
+    pub fn get_type_name(&self) -> &'static str {
+        type_name(self.gl_type)
+    }
+
+    pub fn is_sampler(&self) -> bool {
+        is_sampler(self.gl_type)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct ShaderReflection {
+    uniforms: HashMap<String, ShaderVariable>,
+    attributes: HashMap<String, ShaderVariable>,
+}
+
+impl ShaderReflection {
+    pub fn query(program_id: GLuint) -> Result<Self, String> {
+        Ok(Self {
+            uniforms: Self::query_variables(program_id, VariableKind::Uniform)?,
+            attributes: Self::query_variables(program_id, VariableKind::Attribute)?,
+        })
+    }
+
+    fn query_variables(
+        program_id: GLuint,
+        kind: VariableKind,
+    ) -> Result<HashMap<String, ShaderVariable>, String> {
+        let (count_name, max_length_name) = match kind {
+            VariableKind::Uniform => (gl::ACTIVE_UNIFORMS, gl::ACTIVE_UNIFORM_MAX_LENGTH),
+            VariableKind::Attribute => (gl::ACTIVE_ATTRIBUTES, gl::ACTIVE_ATTRIBUTE_MAX_LENGTH),
+        };
+
+        let mut count = 0;
+        let mut max_length = 0;
+
+        gl_exec!(|| gl::GetProgramiv(program_id, count_name, &mut count))?;
+        gl_exec!(|| gl::GetProgramiv(program_id, max_length_name, &mut max_length))?;
+
+        let mut variables = HashMap::new();
+        let mut buffer = vec![0 as GLchar; max_length.max(1) as usize];
+
+        for index in 0..count as GLuint {
+            let mut length: GLsizei = 0;
+            let mut size: GLint = 0;
+            let mut gl_type: GLenum = 0;
+
+            match kind {
+                VariableKind::Uniform => gl_exec!(|| gl::GetActiveUniform(
+                    program_id,
+                    index,
+                    buffer.len() as GLsizei,
+                    &mut length,
+                    &mut size,
+                    &mut gl_type,
+                    buffer.as_mut_ptr()
+                ))?,
+                VariableKind::Attribute => gl_exec!(|| gl::GetActiveAttrib(
+                    program_id,
+                    index,
+                    buffer.len() as GLsizei,
+                    &mut length,
+                    &mut size,
+                    &mut gl_type,
+                    buffer.as_mut_ptr()
+                ))?,
+            }
+
+            let mut location = -1;
+
+            match kind {
+                VariableKind::Uniform => {
+                    gl_exec!(|| location = gl::GetUniformLocation(program_id, buffer.as_ptr()))?
+                }
+                VariableKind::Attribute => {
+                    gl_exec!(|| location = gl::GetAttribLocation(program_id, buffer.as_ptr()))?
+                }
+            }
+
+            let raw_name: Vec<u8> = buffer[..length as usize].iter().map(|&c| c as u8).collect();
+            let raw_name = String::from_utf8_lossy(&raw_name);
+
+            // Les tableaux sont rapportés sous la forme `nom[0]`.
+            let name = raw_name
+                .strip_suffix("[0]")
+                .unwrap_or(&raw_name)
+                .to_string();
+
+            variables.insert(
+                name.clone(),
+                ShaderVariable {
+                    name,
+                    gl_type,
+                    size,
+                    location,
+                },
+            );
+        }
+
+        Ok(variables)
+    }
+
+    pub fn get_uniform(&self, name: &str) -> Option<&ShaderVariable> {
+        self.uniforms.get(name)
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&ShaderVariable> {
+        self.attributes.get(name)
+    }
+
+    pub fn has_uniform(&self, name: &str) -> bool {
+        self.uniforms.contains_key(name)
+    }
+
+    pub fn borrow_uniforms(&self) -> &HashMap<String, ShaderVariable> {
+        &self.uniforms
+    }
+
+    pub fn borrow_attributes(&self) -> &HashMap<String, ShaderVariable> {
+        &self.attributes
+    }
+}
+
+pub fn is_sampler(gl_type: GLenum) -> bool {
+    matches!(
+        gl_type,
+        gl::SAMPLER_1D
+            | gl::SAMPLER_2D
+            | gl::SAMPLER_3D
+            | gl::SAMPLER_CUBE
+            | gl::SAMPLER_2D_ARRAY
+            | gl::SAMPLER_2D_SHADOW
+            | gl::SAMPLER_BUFFER
+            | gl::INT_SAMPLER_2D
+            | gl::UNSIGNED_INT_SAMPLER_2D
+    )
+}
+
+// Nom GLSL du type, utilisé dans les messages d'erreur.
+pub fn type_name(gl_type: GLenum) -> &'static str {
+    match gl_type {
+        gl::BOOL => "bool",
+        gl::INT => "int",
+        gl::UNSIGNED_INT => "uint",
+        gl::FLOAT => "float",
+        gl::FLOAT_VEC2 => "vec2",
+        gl::FLOAT_VEC3 => "vec3",
+        gl::FLOAT_VEC4 => "vec4",
+        gl::INT_VEC2 => "ivec2",
+        gl::INT_VEC3 => "ivec3",
+        gl::INT_VEC4 => "ivec4",
+        gl::BOOL_VEC2 => "bvec2",
+        gl::BOOL_VEC3 => "bvec3",
+        gl::BOOL_VEC4 => "bvec4",
+        gl::FLOAT_MAT2 => "mat2",
+        gl::FLOAT_MAT3 => "mat3",
+        gl::FLOAT_MAT4 => "mat4",
+        gl::SAMPLER_1D => "sampler1D",
+        gl::SAMPLER_2D => "sampler2D",
+        gl::SAMPLER_3D => "sampler3D",
+        gl::SAMPLER_CUBE => "samplerCube",
+        gl::SAMPLER_2D_ARRAY => "sampler2DArray",
+        gl::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        gl::SAMPLER_BUFFER => "samplerBuffer",
+        gl::INT_SAMPLER_2D => "isampler2D",
+        gl::UNSIGNED_INT_SAMPLER_2D => "usampler2D",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{
+    preprocessor::{normalize_defines, ShaderDefines, ShaderPreprocessor},
+    program::ShaderProgram,
+};
+
+#[derive(Clone)]
+pub enum ShaderSource {
+    // Source compilée dans l'exécutable, jamais rechargée.
+    Embedded { name: String, source: String },
+    // Fichier GLSL relu à chaque rechargement.
+    File(PathBuf),
+}
+
+impl ShaderSource {
+    pub fn embedded(name: &str, source: &str) -> Self {
+        Self::Embedded {
+            name: name.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::File(path.into())
+    }
+
+    fn read(&self) -> Result<(String, String), String> {
+        match self {
+            ShaderSource::Embedded { name, source } => Ok((name.clone(), source.clone())),
+            ShaderSource::File(path) => {
+                let source = fs::read_to_string(path)
+                    .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+
+                Ok((path.display().to_string(), source))
+            }
+        }
+    }
+
+    fn modified(&self) -> Option<SystemTime> {
+        match self {
+            ShaderSource::Embedded { .. } => None,
+            ShaderSource::File(path) => fs::metadata(path).and_then(|m| m.modified()).ok(),
+        }
+    }
+}
+
+struct RegisteredProgram {
+    program: ShaderProgram,
+    vertex: ShaderSource,
+    fragment: ShaderSource,
+    // Dates de modification lors du dernier chargement, réussi ou non.
+    modified: (Option<SystemTime>, Option<SystemTime>),
+    // Permutations compilées avec d'autres `#define`, rechargées avec le programme de base.
+    variants: HashMap<ShaderDefines, ShaderProgram>,
+}
+
+// Programmes nommés, chargés depuis des sources intégrées ou des fichiers. Les fichiers peuvent
+// être surveillés pendant le développement afin de recompiler les programmes à la volée.
+pub struct ShaderRegistry {
+    programs: HashMap<String, RegisteredProgram>,
+    preprocessor: ShaderPreprocessor,
+    watching: bool,
+    poll_interval: Duration,
+    last_poll: Instant,
+}
+
+impl Default for ShaderRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShaderRegistry {
+    pub fn new() -> Self {
+        Self {
+            programs: HashMap::new(),
+            preprocessor: ShaderPreprocessor::new(),
+            watching: false,
+            poll_interval: Duration::from_millis(500),
+            last_poll: Instant::now(),
+        }
+    }
+
+    // Passe les sources dans le préprocesseur puis compile le programme.
+    fn compile(
+        &self,
+        vertex: &ShaderSource,
+        fragment: &ShaderSource,
+        defines: &ShaderDefines,
+    ) -> Result<ShaderProgram, String> {
+        let (vertex_name, vertex_source) = vertex.read()?;
+        let (fragment_name, fragment_source) = fragment.read()?;
+
+        let vertex_source = self
+            .preprocessor
+            .process(&vertex_source, defines)
+            .map_err(|e| format!("{vertex_name}: {e}"))?;
+        let fragment_source = self
+            .preprocessor
+            .process(&fragment_source, defines)
+            .map_err(|e| format!("{fragment_name}: {e}"))?;
+
+        ShaderProgram::from_sources(
+            &vertex_name,
+            &vertex_source,
+            &fragment_name,
+            &fragment_source,
+        )
+    }
+
+    // Recompile en place le programme et ses permutations avec ces sources. Tout est d'abord
+    // compilé dans des programmes temporaires : au moindre échec, aucun programme n'est modifié.
+    fn recompile(
+        &self,
+        registered: &RegisteredProgram,
+        vertex: &ShaderSource,
+        fragment: &ShaderSource,
+    ) -> Result<(), String> {
+        let mut compiled = vec![(
+            &registered.program,
+            self.compile(vertex, fragment, &Vec::new())?,
+        )];
+
+        for (defines, variant) in registered.variants.iter() {
+            compiled.push((variant, self.compile(vertex, fragment, defines)?));
+        }
+
+        for (program, temporary) in compiled {
+            program.relink_with(&temporary)?;
+        }
+
+        Ok(())
+    }
+
+    // Compile et enregistre un programme, un programme du même nom et ses permutations sont
+    // remplacés en place. En cas d'échec l'ancienne version reste utilisée.
+    pub fn load(
+        &mut self,
+        name: &str,
+        vertex: ShaderSource,
+        fragment: ShaderSource,
+    ) -> Result<ShaderProgram, String> {
+        let modified = (vertex.modified(), fragment.modified());
+
+        if let Some(registered) = self.programs.get(name) {
+            self.recompile(registered, &vertex, &fragment)?;
+        }
+
+        if let Some(registered) = self.programs.get_mut(name) {
+            registered.vertex = vertex;
+            registered.fragment = fragment;
+            registered.modified = modified;
+
+            return Ok(registered.program.clone());
+        }
+
+        let program = self.compile(&vertex, &fragment, &Vec::new())?;
+
+        program.set_label(name);
+
+        self.programs.insert(
+            name.to_string(),
+            RegisteredProgram {
+                program: program.clone(),
+                vertex,
+                fragment,
+                modified,
+                variants: HashMap::new(),
+            },
+        );
+
+        Ok(program)
+    }
+
+    // Retourne la permutation du programme compilée avec ces `#define`, en la compilant au
+    // premier appel.
+    pub fn get_variant(
+        &mut self,
+        name: &str,
+        defines: &[(&str, &str)],
+    ) -> Result<ShaderProgram, String> {
+        let defines = normalize_defines(defines);
+
+        let registered = self
+            .programs
+            .get(name)
+            .ok_or(format!("no shader program named '{name}'"))?;
+
+        if defines.is_empty() {
+            return Ok(registered.program.clone());
+        }
+
+        if let Some(variant) = registered.variants.get(&defines) {
+            return Ok(variant.clone());
+        }
+
+        let variant = self.compile(&registered.vertex, &registered.fragment, &defines)?;
+
+        let label: Vec<String> = defines.iter().map(|(define, _)| define.clone()).collect();
+        variant.set_label(&format!("{name} [{}]", label.join(", ")));
+
+        if let Some(registered) = self.programs.get_mut(name) {
+            registered.variants.insert(defines, variant.clone());
+        }
+
+        Ok(variant)
+    }
+
+    pub fn load_files(
+        &mut self,
+        name: &str,
+        vertex_path: impl Into<PathBuf>,
+        fragment_path: impl Into<PathBuf>,
+    ) -> Result<ShaderProgram, String> {
+        self.load(
+            name,
+            ShaderSource::file(vertex_path),
+            ShaderSource::file(fragment_path),
+        )
+    }
+
+    pub fn get(&self, name: &str) -> Option<ShaderProgram> {
+        self.programs
+            .get(name)
+            .map(|registered| registered.program.clone())
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.programs.keys().cloned().collect()
+    }
+
+    // Le programme reste utilisable par les objets qui en ont une copie.
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.programs.remove(name).is_some()
+    }
+
+    // Relit les sources du programme et de ses permutations. Si l'une d'elles ne compile pas,
+    // l'ancienne version de tous les programmes reste utilisée.
+    pub fn reload(&mut self, name: &str) -> Result<(), String> {
+        let registered = self
+            .programs
+            .get_mut(name)
+            .ok_or(format!("no shader program named '{name}'"))?;
+
+        registered.modified = (registered.vertex.modified(), registered.fragment.modified());
+
+        let registered = &self.programs[name];
+
+        self.recompile(registered, &registered.vertex, &registered.fragment)
+    }
+
+    pub fn borrow_preprocessor(&self) -> &ShaderPreprocessor {
+        &self.preprocessor
+    }
+
+    // Les fichiers ajoutés au préprocesseur peuvent être inclus par tous les programmes.
+    pub fn borrow_preprocessor_mut(&mut self) -> &mut ShaderPreprocessor {
+        &mut self.preprocessor
+    }
+
+    pub fn set_watching(&mut self, value: bool) {
+        self.watching = value;
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watching
+    }
+
+    pub fn set_poll_interval(&mut self, interval: Duration) {
+        self.poll_interval = interval;
+    }
+
+    // Recharge les programmes dont un fichier a changé et retourne le résultat pour chacun.
+    // Ne fait rien si la surveillance est désactivée ou si le dernier passage est trop récent.
+    pub fn poll_changes(&mut self) -> Vec<(String, Result<(), String>)> {
+        if !self.watching || self.last_poll.elapsed() < self.poll_interval {
+            return Vec::new();
+        }
+
+        self.last_poll = Instant::now();
+
+        let changed: Vec<String> = self
+            .programs
+            .iter()
+            .filter(|(_, registered)| {
+                let modified = (registered.vertex.modified(), registered.fragment.modified());
+
+                modified != registered.modified
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        changed
+            .into_iter()
+            .map(|name| {
+                let result = self.reload(&name);
+
+                (name, result)
+            })
+            .collect()
+    }
+}
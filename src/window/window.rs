@@ -1,4 +1,9 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    rc::Rc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use sdl2::{
     event::{Event, WindowEvent},
@@ -7,28 +12,33 @@ use sdl2::{
     EventPump, Sdl,
 };
 
-use crate::maths::mat::Mat4;
 use crate::maths::vec::Vec2;
 use crate::{
     benchmark::BenchmarkManager,
     gl_exec,
     message::MessageCaller,
-    renderer::{draw::Draw, Renderer},
+    punk_error, punk_info,
+    renderer::{debug, draw::Draw, Renderer},
     resource::Resource,
+    shader::registry::ShaderRegistry,
     types::{UserData, RGB},
 };
 
 use super::user_input::{KeyStatus, Keys};
 
 pub struct Window<'a> {
+    // Libéré avant la fenêtre SDL afin que les objets OpenGL soient supprimés avec un contexte
+    // encore valide.
+    pub(crate) renderer: Renderer,
     sdl: Sdl,
     window: sdl2::video::Window,
     event_pump: EventPump,
-    pub(crate) renderer: Renderer,
     keys: Keys,
     background_color: RGB,
     update_callback: Box<dyn FnMut(&Keys, &mut UserData) + 'a>,
     user_data: UserData,
+    screenshot_hotkey: Option<Keycode>,
+    screenshot_directory: PathBuf,
 }
 
 impl<'a> Window<'a> {
@@ -47,6 +57,11 @@ impl<'a> Window<'a> {
         gl_attr.set_context_profile(sdl2::video::GLProfile::Core);
         gl_attr.set_context_version(3, 3);
 
+        // Un contexte de débogage est nécessaire pour recevoir les messages de `GL_KHR_debug`.
+        if cfg!(debug_assertions) {
+            gl_attr.set_context_flags().debug().set();
+        }
+
         // Crée la fenêtre.
         let window = match video_subsystem
             .window(title, width, height)
@@ -72,7 +87,11 @@ impl<'a> Window<'a> {
             video_subsystem.gl_get_proc_address(proc_name) as *const std::os::raw::c_void
         });
 
-        let renderer = Renderer::build(gl_context, display_size)?;
+        if cfg!(debug_assertions) {
+            debug::enable_debug_output();
+        }
+
+        let mut renderer = Renderer::build(gl_context, display_size)?;
         if let Err(err) = renderer.set_viewport_size(width as i32, height as i32) {
             return Err(err);
         }
@@ -89,6 +108,8 @@ impl<'a> Window<'a> {
             background_color: RGB::new(0, 0, 0),
             update_callback: Box::new(|_, _| {}),
             user_data: UserData { data: None },
+            screenshot_hotkey: None,
+            screenshot_directory: PathBuf::from("."),
         })
     }
 
@@ -98,11 +119,14 @@ impl<'a> Window<'a> {
         drawing_objects: &mut Vec<Box<dyn Draw>>,
         message_caller: Rc<RefCell<MessageCaller>>,
         benchmark: &mut BenchmarkManager,
+        shaders: &mut ShaderRegistry,
     ) -> Result<(), String> {
         // Boucle infinie de la fenêtre.
         'running: loop {
             self.keys.update_last_key_states();
 
+            let mut screenshot_requested = false;
+
             for event in self.event_pump.poll_iter() {
                 match event {
                     Event::Quit { .. }
@@ -115,6 +139,10 @@ impl<'a> Window<'a> {
                         repeat: false,
                         ..
                     } => {
+                        if keycode.is_some() && keycode == self.screenshot_hotkey {
+                            screenshot_requested = true;
+                        }
+
                         if let Some(key) = keycode {
                             match key {
                                 Keycode::Num0 => self.keys.set_key_state("0", KeyStatus::Pressed),
@@ -211,57 +239,31 @@ impl<'a> Window<'a> {
                         win_event: WindowEvent::Resized(width, height),
                         ..
                     } => {
+                        // Recalcule aussi la zone visible afin de remplir l'espace disponible
+                        // sans déformer le rendu.
                         if let Err(err) = self.renderer.set_viewport_size(width, height) {
                             eprintln!("{err}");
                         }
-
-                        let display_size = self.renderer.get_display_size();
-                        let display_aspect_ratio = display_size.x as f32 / display_size.y as f32;
-
-                        // Le rapport d'aspect permet d'agrandir / réduire le rendu afin de
-                        // remplir l'espace disponible.
-                        let aspect_ratio = width as f32 / height as f32;
-                        self.renderer.aspect_ratio = aspect_ratio;
-
-                        // Si la largeur est plus grande que la hauteur alors il faut scale sur
-                        // la largeur.
-                        if aspect_ratio >= display_aspect_ratio {
-                            // Viewport plus large, utilise toute la hauteur.
-                            self.renderer.left = -aspect_ratio / display_aspect_ratio
-                                * display_size.x as f32
-                                / 2.0_f32;
-                            self.renderer.bottom = display_size.y as f32 / 2.0_f32;
-
-                            self.renderer.projection = Mat4::ortho(
-                                self.renderer.left as i32 - 1,
-                                -self.renderer.left as i32,
-                                self.renderer.bottom as i32,
-                                -self.renderer.bottom as i32 - 1,
-                                -100.0_f32,
-                                100.0_f32,
-                            );
-                        // Sinon il faut scale sur la hauteur.
-                        } else {
-                            // Viewport plus haut, utilise toute la largeur.
-                            self.renderer.left = -display_size.x as f32 / 2.0_f32;
-                            self.renderer.bottom = display_aspect_ratio / aspect_ratio
-                                * display_size.y as f32
-                                / 2.0_f32;
-
-                            self.renderer.projection = Mat4::ortho(
-                                self.renderer.left as i32 - 1,
-                                -self.renderer.left as i32,
-                                self.renderer.bottom as i32,
-                                -self.renderer.bottom as i32,
-                                -100.0_f32,
-                                100.0_f32,
-                            );
-                        }
                     }
                     _ => {}
                 }
             }
 
+            if screenshot_requested {
+                if let Err(err) = self.take_hotkey_screenshot(drawing_objects) {
+                    eprintln!("{err}");
+                }
+            }
+
+            // Recompile les shaders modifiés sur le disque, l'ancienne version est gardée en cas
+            // d'erreur.
+            for (name, result) in shaders.poll_changes() {
+                match result {
+                    Ok(()) => punk_info!("shader program '{name}' reloaded"),
+                    Err(err) => punk_error!("cannot reload shader program '{name}': {err}"),
+                }
+            }
+
             // Appelle la fonction de callback pour mettre à jour l'état du moteur et du programme.
             benchmark.bench("graph_punk_update_callback".to_string(), || {
                 (self.update_callback)(&self.keys, &mut self.user_data);
@@ -273,6 +275,23 @@ impl<'a> Window<'a> {
                     .execute(&mut self.renderer, drawing_objects, resource);
             });
 
+            self.renderer.begin_frame();
+
+            // Dessine les objets redirigés dans leurs cibles de rendu.
+            if let Err(err) = self.renderer.gpu_timer.begin("graph_punk_render_targets") {
+                eprintln!("{err}");
+            }
+
+            benchmark.bench("graph_punk_render_targets".to_string(), || {
+                if let Err(err) = self.renderer.render_targets(drawing_objects) {
+                    eprintln!("{err}");
+                }
+            });
+
+            if let Err(err) = self.renderer.gpu_timer.end() {
+                eprintln!("{err}");
+            }
+
             // Défini la couleur qu'OpenGL va utiliser pour nettoyer l'écran.
             if let Err(err) = gl_exec!(|| gl::ClearColor(
                 self.background_color.r as f32 / 255.0 as f32,
@@ -283,17 +302,47 @@ impl<'a> Window<'a> {
                 return Err(err);
             }
 
+            let mut post_processing = self.renderer.post_process.is_active();
+
+            // Avec des effets de post-traitement, la scène est d'abord dessinée hors écran. En
+            // cas d'erreur, la frame est dessinée directement dans la fenêtre.
+            if post_processing {
+                let viewport_size = self.renderer.get_viewport_size();
+
+                if let Err(err) = self.renderer.post_process.begin(&viewport_size) {
+                    eprintln!("{err}");
+
+                    post_processing = false;
+
+                    if let Err(err) = self.renderer.bind_default_framebuffer() {
+                        eprintln!("{err}");
+                    }
+                }
+            }
+
             // Nettoie l'écran.
             if let Err(err) = gl_exec!(|| gl::Clear(gl::COLOR_BUFFER_BIT)) {
                 return Err(err);
             }
 
+            let redirected_indexes = self.renderer.redirected_indexes();
+
+            self.renderer.update_projection();
+
             // Dessine tous les objets.
+            if let Err(err) = self.renderer.gpu_timer.begin("graph_punk_rendering") {
+                eprintln!("{err}");
+            }
+
             benchmark.bench("graph_punk_rendering".to_string(), || {
-                for drawing_object in drawing_objects.iter() {
-                    if drawing_object.is_visible() {
-                        if let Err(err) =
-                            drawing_object.draw(&self.renderer, &self.renderer.projection)
+                for (index, drawing_object) in drawing_objects.iter().enumerate() {
+                    if drawing_object.is_visible() && !redirected_indexes.contains(&index) {
+                        if let Err(err) = self
+                            .renderer
+                            .set_blend_mode(drawing_object.get_blend_mode())
+                            .and_then(|_| {
+                                drawing_object.draw(&self.renderer, &self.renderer.projection)
+                            })
                         {
                             eprintln!("{err}");
 
@@ -301,14 +350,97 @@ impl<'a> Window<'a> {
                         }
                     }
                 }
-                // Met à jour le contenu dessiné sur la fenêtre.
+            });
+
+            if let Err(err) = self.renderer.gpu_timer.end() {
+                eprintln!("{err}");
+            }
+
+            if post_processing {
+                if let Err(err) = self.renderer.gpu_timer.begin("graph_punk_post_process") {
+                    eprintln!("{err}");
+                }
+
+                benchmark.bench("graph_punk_post_process".to_string(), || {
+                    if let Err(err) = self.renderer.post_process.apply(
+                        &self.renderer.state,
+                        &self.renderer.get_viewport_size(),
+                        &self.renderer.get_display_size(),
+                    ) {
+                        eprintln!("{err}");
+                    }
+                });
+
+                if let Err(err) = self.renderer.gpu_timer.end() {
+                    eprintln!("{err}");
+                }
+            }
+
+            if let Err(err) = self.renderer.save_pending_screenshots() {
+                eprintln!("{err}");
+            }
+
+            benchmark.bench("graph_punk_recording".to_string(), || {
+                if let Err(err) = self
+                    .renderer
+                    .record_frame(drawing_objects, &self.background_color)
+                {
+                    eprintln!("{err}");
+                }
+            });
+
+            // Met à jour le contenu dessiné sur la fenêtre. Mesuré à part car l'attente de la
+            // synchronisation verticale et du pilote se retrouve ici.
+            benchmark.bench("graph_punk_swap_window".to_string(), || {
                 self.window.gl_swap_window();
             });
+
+            // Les temps GPU des frames précédentes sont rangés à côté des temps CPU.
+            match self.renderer.gpu_timer.collect() {
+                Ok(timings) => {
+                    for (name, duration) in timings {
+                        benchmark.record(format!("{name}_gpu"), duration);
+                    }
+                }
+                Err(err) => eprintln!("{err}"),
+            }
         }
 
+        // Termine un éventuel enregistrement afin de ne pas perdre les frames en mémoire.
+        self.renderer.set_recorder(None)?;
+
         Ok(())
     }
 
+    // Sauvegarde la prochaine frame ainsi que la grille de pixels logique.
+    fn take_hotkey_screenshot(&mut self, drawing_objects: &[Box<dyn Draw>]) -> Result<(), String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+
+        self.renderer.request_screenshot(
+            self.screenshot_directory
+                .join(format!("screenshot_{timestamp}.png")),
+        );
+
+        self.renderer
+            .capture_grid(drawing_objects, &self.background_color)?
+            .save_png(
+                self.screenshot_directory
+                    .join(format!("screenshot_{timestamp}_grid.png")),
+            )
+    }
+
+    pub fn set_screenshot_hotkey(&mut self, hotkey: Option<Keycode>, directory: PathBuf) {
+        self.screenshot_hotkey = hotkey;
+        self.screenshot_directory = directory;
+    }
+
+    pub fn borrow_background_color(&self) -> &RGB {
+        &self.background_color
+    }
+
     pub fn set_display_size(&mut self, size: Vec2<i32>) {
         self.renderer.set_display_size(size);
     }